@@ -0,0 +1,45 @@
+/*
+ * Compose sequences, these allow accented characters to be typed on a
+ * keyboard that does not have them, for example typing `e'` produces `é`
+ * */
+
+use crate::impfile;
+use std::collections::HashMap;
+
+pub struct ComposeTable {
+    pub language: String,
+    //(first character, second character) -> composed character
+    sequences: HashMap<(char, char), char>,
+}
+
+impl ComposeTable {
+    //Returns the character that `first` followed by `second` composes into
+    pub fn compose(&self, first: char, second: char) -> Option<char> {
+        self.sequences.get(&(first, second)).copied()
+    }
+}
+
+//Loads compose tables from an impfile, each entry is a language and each
+//variable is a sequence of two characters and the character it produces
+pub fn load_compose_tables(path: &str) -> Vec<ComposeTable> {
+    impfile::find_impfile(path)
+        .iter()
+        .map(|entry| {
+            let mut sequences = HashMap::new();
+            for (sequence, composed) in entry.get_var_list() {
+                let seq: Vec<char> = sequence.chars().collect();
+                let composed: Vec<char> = composed.chars().collect();
+                if seq.len() != 2 || composed.len() != 1 {
+                    eprintln!("Invalid compose sequence: \"{sequence}\"");
+                    continue;
+                }
+                sequences.insert((seq[0], seq[1]), composed[0]);
+            }
+
+            ComposeTable {
+                language: entry.get_name(),
+                sequences,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,318 @@
+/*
+ * Exam mode: a fixed number of cards are each asked once with no feedback
+ * until the end, the player is then shown a grade and can save a report
+ * */
+
+use crate::{
+    flashcards::Flashcard,
+    impfile::Entry,
+    learn,
+    progress::{self, set_name},
+};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+pub const MAX_EXAM_QUESTIONS: usize = 100;
+//In minutes
+pub const MAX_TIME_LIMIT: u32 = 120;
+
+#[derive(Clone)]
+pub struct ExamConfig {
+    //Number of cards drawn from the set, 0 means every card
+    pub question_count: usize,
+    //Overall time limit (in minutes), 0 means there is no limit
+    pub time_limit: u32,
+}
+
+impl ExamConfig {
+    pub fn new() -> Self {
+        Self {
+            question_count: 20,
+            time_limit: 0,
+        }
+    }
+
+    pub fn from_entry(entry: &Entry) -> Self {
+        let default = Self::new();
+        Self {
+            question_count: entry
+                .get_var("question_count")
+                .parse()
+                .unwrap_or(default.question_count)
+                .min(MAX_EXAM_QUESTIONS),
+            time_limit: entry
+                .get_var("time_limit")
+                .parse()
+                .unwrap_or(default.time_limit)
+                .min(MAX_TIME_LIMIT),
+        }
+    }
+
+    pub fn to_entry(&self) -> Entry {
+        let mut entry = Entry::new("exam");
+        entry.add_integer("question_count", self.question_count as i64);
+        entry.add_integer("time_limit", self.time_limit as i64);
+        entry
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+    Csv,
+}
+
+impl ReportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+pub struct ExamResult {
+    pub card: Flashcard,
+    //Empty if the question was not answered
+    pub answer: String,
+    pub correct: bool,
+}
+
+pub struct ExamState {
+    questions: Vec<Flashcard>,
+    answers: Vec<String>,
+    //Index of the current question
+    pub current: usize,
+    pub time: f32,
+    //In seconds, 0 means there is no limit
+    time_limit: f32,
+    //Set to true when every question is answered or time runs out
+    finished: bool,
+    //Path of the last saved report (or an error message)
+    pub report_message: String,
+}
+
+//Escapes characters that have a meaning in a markdown table
+fn escape_markdown(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('|', "\\|")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn escape_csv(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+impl ExamState {
+    pub fn new(cards: &[Flashcard], config: &ExamConfig, rng: &mut StdRng) -> Self {
+        let mut questions = cards.to_vec();
+        questions.shuffle(rng);
+        if config.question_count > 0 {
+            questions.truncate(config.question_count);
+        }
+
+        Self {
+            answers: vec![String::new(); questions.len()],
+            questions,
+            current: 0,
+            time: 0.0,
+            time_limit: config.time_limit as f32 * 60.0,
+            finished: false,
+            report_message: String::new(),
+        }
+    }
+
+    pub fn empty() -> Self {
+        Self {
+            questions: vec![],
+            answers: vec![],
+            current: 0,
+            time: 0.0,
+            time_limit: 0.0,
+            finished: true,
+            report_message: String::new(),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.questions.len()
+    }
+
+    pub fn finished(&self) -> bool {
+        self.finished || self.current >= self.questions.len()
+    }
+
+    pub fn get_flashcard(&self) -> Option<Flashcard> {
+        if self.finished() {
+            return None;
+        }
+        self.questions.get(self.current).cloned()
+    }
+
+    pub fn percent(&self) -> f32 {
+        if self.questions.is_empty() {
+            return 1.0;
+        }
+        self.current as f32 / self.questions.len() as f32
+    }
+
+    //Returns the number of seconds left, None if there is no time limit
+    pub fn time_left(&self) -> Option<f32> {
+        if self.time_limit <= 0.0 {
+            return None;
+        }
+        Some((self.time_limit - self.time).max(0.0))
+    }
+
+    pub fn submit(&mut self, ans: &str) {
+        if self.finished() {
+            return;
+        }
+        self.answers[self.current] = ans.to_string();
+        self.current += 1;
+    }
+
+    //End the exam early, unanswered questions are marked as incorrect
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        if self.finished() {
+            return;
+        }
+
+        self.time += dt;
+        if self.time_limit > 0.0 && self.time >= self.time_limit {
+            self.time = self.time_limit;
+            self.finish();
+        }
+    }
+
+    pub fn results(&self) -> Vec<ExamResult> {
+        self.questions
+            .iter()
+            .zip(&self.answers)
+            .map(|(card, answer)| ExamResult {
+                card: card.clone(),
+                answer: answer.clone(),
+                correct: learn::check_answer(card, answer),
+            })
+            .collect()
+    }
+
+    pub fn correct_count(&self) -> usize {
+        self.results().iter().filter(|res| res.correct).count()
+    }
+
+    //Percentage grade (0 - 100)
+    pub fn grade(&self) -> f32 {
+        if self.questions.is_empty() {
+            return 0.0;
+        }
+        self.correct_count() as f32 / self.questions.len() as f32 * 100.0
+    }
+
+    fn markdown_report(&self, set: &str, player: &str) -> String {
+        let mut report = format!("# Exam report: {}\n\n", escape_markdown(set));
+        report.push_str(&format!("- Player: {}\n", escape_markdown(player)));
+        report.push_str(&format!(
+            "- Grade: {:.1}% ({} of {} correct)\n",
+            self.grade(),
+            self.correct_count(),
+            self.size()
+        ));
+        report.push_str(&format!("- Time: {:.0} seconds\n\n", self.time));
+        report.push_str("| # | Question | Answer | Correct answer | Result |\n");
+        report.push_str("|---|---|---|---|---|\n");
+        for (i, res) in self.results().iter().enumerate() {
+            report.push_str(&format!(
+                "| {} | {} | {} | {} | {} |\n",
+                i + 1,
+                escape_markdown(&res.card.question),
+                escape_markdown(&res.answer),
+                escape_markdown(&res.card.answer),
+                if res.correct { "Correct" } else { "Incorrect" }
+            ));
+        }
+        report
+    }
+
+    fn html_report(&self, set: &str, player: &str) -> String {
+        let title = format!("Exam report: {}", escape_html(set));
+        let mut report = String::from("<!DOCTYPE html>\n<html>\n<head>\n");
+        report.push_str("<meta charset=\"utf-8\">\n");
+        report.push_str(&format!("<title>{title}</title>\n</head>\n<body>\n"));
+        report.push_str(&format!("<h1>{title}</h1>\n<ul>\n"));
+        report.push_str(&format!("<li>Player: {}</li>\n", escape_html(player)));
+        report.push_str(&format!(
+            "<li>Grade: {:.1}% ({} of {} correct)</li>\n",
+            self.grade(),
+            self.correct_count(),
+            self.size()
+        ));
+        report.push_str(&format!("<li>Time: {:.0} seconds</li>\n</ul>\n", self.time));
+        report.push_str("<table border=\"1\">\n");
+        report.push_str("<tr><th>#</th><th>Question</th><th>Answer</th>");
+        report.push_str("<th>Correct answer</th><th>Result</th></tr>\n");
+        for (i, res) in self.results().iter().enumerate() {
+            report.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                i + 1,
+                escape_html(&res.card.question),
+                escape_html(&res.answer),
+                escape_html(&res.card.answer),
+                if res.correct { "Correct" } else { "Incorrect" }
+            ));
+        }
+        report.push_str("</table>\n</body>\n</html>\n");
+        report
+    }
+
+    fn csv_report(&self) -> String {
+        let mut report = String::from("number,question,answer,correct_answer,correct\n");
+        for (i, res) in self.results().iter().enumerate() {
+            report.push_str(&format!(
+                "{},{},{},{},{}\n",
+                i + 1,
+                escape_csv(&res.card.question),
+                escape_csv(&res.answer),
+                escape_csv(&res.card.answer),
+                res.correct
+            ));
+        }
+        report
+    }
+
+    //Saves a report in the reports directory for the player, the path of the
+    //report is stored in `report_message`
+    pub fn save_report(&mut self, format: ReportFormat, player: &str, set_path: &str) {
+        let set = set_name(set_path);
+        let report = match format {
+            ReportFormat::Markdown => self.markdown_report(&set, player),
+            ReportFormat::Html => self.html_report(&set, player),
+            ReportFormat::Csv => self.csv_report(),
+        };
+        let file_name = format!("{set}-{}.{}", progress::now(), format.extension());
+        let path = progress::player_file_path("reports", player, &file_name);
+        self.report_message = match std::fs::write(&path, report) {
+            Ok(()) => format!("Saved report to {path}"),
+            Err(msg) => {
+                eprintln!("Failed to save report: {path}");
+                eprintln!("{msg}");
+                format!("Failed to save report: {msg}")
+            }
+        };
+    }
+}
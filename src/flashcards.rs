@@ -1,11 +1,17 @@
+mod tests;
+
 use crate::impfile;
+use std::collections::BTreeSet;
 
 pub const SET_PATH: &str = "sets";
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq)]
 pub struct Flashcard {
     pub question: String,
     pub answer: String,
+    //Name of the entry that the card was loaded from, cards in the same
+    //entry are usually related to each other
+    pub group: String,
 }
 
 impl Flashcard {
@@ -13,24 +19,36 @@ impl Flashcard {
         Self {
             question: "None".to_string(),
             answer: "None".to_string(),
+            group: String::new(),
         }
     }
 
-    pub fn new(q: &str, a: &str) -> Self {
+    pub fn new(q: &str, a: &str, g: &str) -> Self {
         Self {
             question: q.to_string(),
             answer: a.to_string(),
+            group: g.to_string(),
         }
     }
+
+    //Used to identify a card when saving progress
+    pub fn key(&self) -> (String, String) {
+        (self.question.clone(), self.answer.clone())
+    }
 }
 
 //Load flashcards from an .impfile
-//The question is the variable name, the answer is the variable value
+//The question is the variable name, the answer is the variable value and
+//the group is the name of the entry
 fn load_flashcards_from_file(path: &str) -> Vec<Flashcard> {
     impfile::parse_file(path)
         .iter()
-        .flat_map(|e| e.get_var_list())
-        .map(|(question, answer)| Flashcard::new(&question, &answer))
+        .flat_map(|e| {
+            let group = e.get_name();
+            e.get_var_list()
+                .into_iter()
+                .map(move |(question, answer)| Flashcard::new(&question, &answer, &group))
+        })
         .collect()
 }
 
@@ -41,3 +59,15 @@ pub fn load_flashcards(paths: &[String]) -> Vec<Flashcard> {
         .flat_map(|path| load_flashcards_from_file(path))
         .collect()
 }
+
+//Returns the sorted list of non-ASCII characters that appear in the answers
+//of `cards`, these are displayed in the on-screen character palette
+pub fn special_chars(cards: &[Flashcard]) -> Vec<char> {
+    cards
+        .iter()
+        .flat_map(|card| card.answer.chars())
+        .filter(|ch| !ch.is_ascii())
+        .collect::<BTreeSet<char>>()
+        .into_iter()
+        .collect()
+}
@@ -0,0 +1,15 @@
+#[cfg(test)]
+use super::*;
+
+#[test]
+fn test_cards_in_file_order() {
+    let path = "test_impfiles/testflashcards.impfile".to_string();
+    let cards = load_flashcards(std::slice::from_ref(&path));
+    let questions: Vec<&str> = cards.iter().map(|card| card.question.as_str()).collect();
+    assert_eq!(questions, ["áéíóúàèìòù", "accents", "hello", "fizz", "foo"]);
+
+    //The same set is loaded in the same order every time
+    for _ in 0..10 {
+        assert!(load_flashcards(std::slice::from_ref(&path)) == cards);
+    }
+}
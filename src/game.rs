@@ -1,26 +1,39 @@
 pub mod assets;
+pub mod difficulty;
 pub mod draw;
+pub mod movement;
+pub mod powerup;
+pub mod selection;
+pub mod simulation;
 pub mod sprite;
 pub mod update;
 
 use self::assets::{audio::SfxPlayer, open_file};
 use crate::{
-    flashcards::{Flashcard, SET_PATH},
+    compose::ComposeTable,
+    exam::ExamState,
+    flashcards::{self, Flashcard, SET_PATH},
     gui::GuiController,
+    highscores::{HighScores, ScoreEntry, ScoreMode},
     impfile,
-    learn::LearnState,
-    log::LogItem,
+    learn::{LearnState, QuestionType},
+    matching::MatchState,
+    progress,
+    replay::Replay,
+    settings::Settings,
 };
 use assets::models::ModelManager;
 use assets::shaders::ShaderManager;
 use assets::textures::TextureManager;
-use egui_gl_glfw::egui::{Event, FontDefinitions, MouseWheelUnit, emath};
+use difficulty::{CUSTOM_DIFFICULTY, Difficulty};
+use egui_gl_glfw::egui::{self, Event, FontDefinitions, Modifiers, MouseWheelUnit, emath};
 use glfw::{GlfwReceiver, WindowEvent};
-use sprite::{Asteroid, Explosion};
-use std::{collections::VecDeque, io::Read};
+use powerup::PowerUp;
+use rand::Rng;
+use simulation::Simulation;
+use sprite::Explosion;
+use std::io::Read;
 
-const DEFAULT_SPAWN_INTERVAL: f32 = 8.0;
-const DEFAULT_HEALTH: u32 = 5;
 pub const LEVELUP_ANIMATION_LENGTH: f32 = 2.5; //In seconds
 pub const DAMAGE_ANIMATION_LENGTH: f32 = 1.0; //In seconds
 
@@ -31,31 +44,17 @@ pub enum GameScreen {
     LoadFlashcards,
     Game,
     Learn,
+    Match,
+    Exam,
+    Settings,
 }
 
 //Application config values, these are not meant to be changed by normal users
 #[derive(Default)]
 struct Config {
     font_path: String,
-}
-
-//Calculates how many asteroids are needed to advance to the next level
-//Pass in the current level
-fn calculate_asteroids_until_next(level: u32) -> u32 {
-    match level {
-        1 => 5,
-        2 => 7,
-        3..=5 => 10,
-        6..=8 => 15,
-        9..=12 => 20,
-        13..=15 => 25,
-        16..=18 => 30,
-        _ => 40,
-    }
-}
-
-fn calculate_spawn_interval(level: u32) -> f32 {
-    (DEFAULT_SPAWN_INTERVAL * 0.85f32.powi(level as i32)).max(2.0)
+    show_char_palette: bool,
+    extra_palette_chars: String,
 }
 
 pub struct Game {
@@ -67,34 +66,91 @@ pub struct Game {
     cfg: Config,
     window_w: i32,
     window_h: i32,
-    //Sprites
-    asteroid_spawn_timer: f32,
-    spawn_interval: f32,
-    pub asteroids: Vec<Asteroid>,
+    //Asteroids, scoring, levels and health
+    pub sim: Simulation,
+    //Difficulty presets as (name, difficulty)
+    pub difficulties: Vec<(String, Difficulty)>,
     pub explosions: Vec<Explosion>,
     time: f32,
+    //All random decisions are made with the rng in `sim` so that a session
+    //can be reproduced from its seed
+    pub seed: u64,
+    //Number of simulation steps since the current game started
+    pub step: u64,
+    //The current game, saved as a replay when the game ends
+    recording: Option<Replay>,
+    //Replay that is being watched
+    pub playback: Option<Replay>,
+    //High scores of the set that was played last
+    pub high_scores: HighScores,
+    //Position of the score that was just added to the high scores
+    pub new_high_score: Option<usize>,
+    //Set once the score of the current game is added to the high scores
+    score_recorded: bool,
     pub answer: String,
     pub flashcards: Vec<Flashcard>,
-    //Player info
-    pub health: u32,
-    pub score: u64,
-    pub level: u32,
-    //Every time the player destroys an asteroid, this decreases by 1
-    //When this hits 0, advance to the next level
-    asteroids_until_next_level: u32,
+    //Special characters that can be inserted into the answer with the palette
+    pub char_palette: Vec<char>,
+    //Set when an Alt+number palette shortcut is used so that the number it
+    //would type is dropped
+    skip_next_char: bool,
+    pub compose_tables: Vec<ComposeTable>,
+    //The last character that was typed, used for compose sequences
+    last_typed: Option<char>,
     pub levelup_animation_timer: f32,
     pub damage_animation_timer: f32,
-    pub log: VecDeque<LogItem>,
     pub current_screen: GameScreen,
     pub about_text: Vec<String>,
     pub set_paths: Vec<(String, String)>,
     pub selected_set_path: String,
     //Learn state
     pub learn_state: LearnState,
+    //Path of the set that has a saved learn session that can be resumed
+    pub learn_resume_prompt: Option<String>,
+    //The learn session is paused and the pause/leave prompt is shown
+    pub learn_paused: bool,
+    pub match_state: MatchState,
+    pub exam_state: ExamState,
+    pub settings: Settings,
 }
 
 type EventHandler = GlfwReceiver<(f64, WindowEvent)>;
 
+//Returns the index of the option a number key selects (1 is the first
+//option and 0 is the tenth)
+fn number_key_index(key: glfw::Key) -> Option<usize> {
+    match key {
+        glfw::Key::Num1 | glfw::Key::Kp1 => Some(0),
+        glfw::Key::Num2 | glfw::Key::Kp2 => Some(1),
+        glfw::Key::Num3 | glfw::Key::Kp3 => Some(2),
+        glfw::Key::Num4 | glfw::Key::Kp4 => Some(3),
+        glfw::Key::Num5 | glfw::Key::Kp5 => Some(4),
+        glfw::Key::Num6 | glfw::Key::Kp6 => Some(5),
+        glfw::Key::Num7 | glfw::Key::Kp7 => Some(6),
+        glfw::Key::Num8 | glfw::Key::Kp8 => Some(7),
+        glfw::Key::Num9 | glfw::Key::Kp9 => Some(8),
+        glfw::Key::Num0 | glfw::Key::Kp0 => Some(9),
+        _ => None,
+    }
+}
+
+//Returns true if a key moves the text cursor or edits text without typing
+fn moves_cursor(key: glfw::Key) -> bool {
+    matches!(
+        key,
+        glfw::Key::Backspace
+            | glfw::Key::Delete
+            | glfw::Key::Left
+            | glfw::Key::Right
+            | glfw::Key::Up
+            | glfw::Key::Down
+            | glfw::Key::Home
+            | glfw::Key::End
+            | glfw::Key::Tab
+            | glfw::Key::Escape
+    )
+}
+
 fn handle_window_resize(gamestate: &mut Game, w: i32, h: i32) {
     unsafe {
         gl::Viewport(0, 0, w, h);
@@ -105,6 +161,7 @@ fn handle_window_resize(gamestate: &mut Game, w: i32, h: i32) {
 
 impl Game {
     pub fn new() -> Self {
+        let seed = rand::random();
         Self {
             textures: TextureManager::new(),
             shaders: ShaderManager::new(),
@@ -114,25 +171,127 @@ impl Game {
             cfg: Config::default(),
             window_w: 0,
             window_h: 0,
-            asteroid_spawn_timer: 0.0,
-            spawn_interval: calculate_spawn_interval(1),
-            asteroids: vec![],
+            sim: Simulation::new(seed),
+            difficulties: vec![],
             explosions: vec![],
             time: 0.0,
+            seed,
+            step: 0,
+            recording: None,
+            playback: None,
+            high_scores: HighScores::empty(),
+            new_high_score: None,
+            score_recorded: false,
             answer: String::new(),
             flashcards: vec![],
-            health: DEFAULT_HEALTH,
-            score: 0,
-            level: 1,
-            asteroids_until_next_level: calculate_asteroids_until_next(1),
+            char_palette: vec![],
+            skip_next_char: false,
+            compose_tables: vec![],
+            last_typed: None,
             levelup_animation_timer: 0.0,
             damage_animation_timer: 0.0,
-            log: VecDeque::new(),
             current_screen: GameScreen::Game,
             about_text: vec![],
             set_paths: vec![],
             selected_set_path: String::new(),
             learn_state: LearnState::empty(),
+            learn_resume_prompt: None,
+            learn_paused: false,
+            match_state: MatchState::empty(),
+            exam_state: ExamState::empty(),
+            settings: Settings::new(),
+        }
+    }
+
+    //Restarts the rng so that the rest of the session can be reproduced
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.sim.set_seed(seed);
+    }
+
+    //Returns a seed for a mode that owns its own rng (such as learn mode)
+    pub fn next_seed(&mut self) -> u64 {
+        self.sim.rng().random()
+    }
+
+    //Starts a new game that is recorded so that it can be saved as a replay
+    pub fn start_round(&mut self) {
+        self.save_replay();
+        self.sim.difficulty = self.difficulty();
+        self.restart();
+        let seed = self.next_seed();
+        self.sim.set_seed(seed);
+        self.sim.selector.mode = self.settings.card_selection;
+        self.step = 0;
+        self.playback = None;
+        let set_name = progress::set_name(&self.set_path());
+        let mut replay = Replay::new(seed, &set_name, &self.flashcards);
+        replay.selection = self.sim.selector.mode;
+        replay.priors = self.sim.selector.priors().to_vec();
+        replay.difficulty = self.sim.difficulty.clone();
+        self.recording = Some(replay);
+    }
+
+    //Path of the set that is being played, cards loaded from the command
+    //line are saved under "cards"
+    fn set_path(&self) -> String {
+        if self.selected_set_path.is_empty() {
+            "cards".to_string()
+        } else {
+            self.selected_set_path.clone()
+        }
+    }
+
+    //Adds the score of a finished game to the high scores of the set, this
+    //only happens once per game
+    pub fn record_high_score(&mut self, mode: ScoreMode) {
+        if self.score_recorded || self.playback.is_some() {
+            return;
+        }
+        self.score_recorded = true;
+
+        let (score, level, accuracy) = match mode {
+            ScoreMode::Asteroids => (self.sim.score, self.sim.level, self.sim.accuracy()),
+            ScoreMode::Match => {
+                let match_state = &self.match_state;
+                (match_state.score, 0, match_state.accuracy())
+            }
+        };
+        let entry = ScoreEntry {
+            player: self.settings.player_name.clone(),
+            score,
+            level,
+            accuracy,
+            date: progress::now(),
+        };
+        self.high_scores = HighScores::load(&self.set_path());
+        self.new_high_score = self.high_scores.add(mode, entry);
+        if self.new_high_score.is_some() {
+            self.high_scores.save();
+        }
+    }
+
+    //Watch a recorded game
+    pub fn start_playback(&mut self, mut replay: Replay) {
+        self.save_replay();
+        self.set_flashcards(replay.cards.clone());
+        self.sim.difficulty = replay.difficulty.clone();
+        self.restart();
+        self.sim.set_seed(replay.seed);
+        self.sim.selector.mode = replay.selection;
+        self.sim.selector.set_priors(&replay.priors);
+        self.step = 0;
+        replay.rewind();
+        self.playback = Some(replay);
+        self.current_screen = GameScreen::Game;
+    }
+
+    //Saves the game that is being recorded if the player answered anything
+    pub fn save_replay(&mut self) {
+        if let Some(replay) = self.recording.take()
+            && !replay.is_empty()
+        {
+            replay.save(&self.settings.player_name);
         }
     }
 
@@ -140,32 +299,20 @@ impl Game {
         /*
          * Reset values:
          * -------------
-         * asteroid_spawn_timer: 0.0
-         * spawn_interval: calculate_spawn_interval(1)
-         * asteroids: vec![]
+         * sim: asteroids, health, score, level and log
          * explosions: vec![]
          * answer: String::new()
-         * health: DEFAULT_HEALTH
-         * score: 0
-         * level: 1
-         * asteroids_until_next_level: calculate_asteroids_until_next(1)
          * levelup_animation_timer: 0.0
          * damage_animation_timer: 0.0
-         * log: VecDeque::new(),
          * */
 
-        self.asteroid_spawn_timer = 0.0;
-        self.asteroids.clear();
+        self.sim.restart();
+        self.score_recorded = false;
+        self.new_high_score = None;
         self.explosions.clear();
         self.answer.clear();
-        self.health = DEFAULT_HEALTH;
-        self.score = 0;
-        self.level = 1;
-        self.spawn_interval = calculate_spawn_interval(self.level);
-        self.asteroids_until_next_level = calculate_asteroids_until_next(self.level);
         self.levelup_animation_timer = 0.0;
         self.damage_animation_timer = 0.0;
-        self.log.clear();
     }
 
     pub fn load_config(&mut self, path: &str) {
@@ -176,6 +323,96 @@ impl Game {
         }
         let e = &entries[0];
         self.cfg.font_path = e.get_var("font_path");
+        self.cfg.show_char_palette = e.get_var("show_char_palette") != "false";
+        self.cfg.extra_palette_chars = e.get_var("extra_palette_chars");
+    }
+
+    pub fn load_difficulties(&mut self, path: &str) {
+        self.difficulties = difficulty::load_presets(path);
+    }
+
+    //Returns the difficulty selected in the settings
+    pub fn difficulty(&self) -> Difficulty {
+        if self.settings.difficulty == CUSTOM_DIFFICULTY {
+            return self.settings.custom_difficulty.clone();
+        }
+        self.difficulties
+            .iter()
+            .find(|(name, _)| *name == self.settings.difficulty)
+            .map(|(_, difficulty)| difficulty.clone())
+            .unwrap_or(Difficulty::new())
+    }
+
+    pub fn load_settings(&mut self) {
+        self.settings = Settings::load();
+    }
+
+    //Sets the flashcards and rebuilds the character palette from the answers
+    pub fn set_flashcards(&mut self, cards: Vec<Flashcard>) {
+        self.char_palette.clear();
+        if self.cfg.show_char_palette {
+            self.char_palette = flashcards::special_chars(&cards);
+            for ch in self.cfg.extra_palette_chars.chars() {
+                if !self.char_palette.contains(&ch) {
+                    self.char_palette.push(ch);
+                }
+            }
+        }
+        self.sim.set_cards(&cards);
+        self.flashcards = cards;
+    }
+
+    //Returns the palette character for an Alt+number shortcut
+    //(Alt+1 is the first character, Alt+0 is the tenth)
+    fn palette_shortcut(&self, key: glfw::Key) -> Option<char> {
+        let index = number_key_index(key)?;
+        self.char_palette.get(index).copied()
+    }
+
+    //Returns true if the current multiple choice question can be answered
+    //with the number keys
+    fn answering_mcq(&self) -> bool {
+        self.current_screen == GameScreen::Learn
+            && !self.learn_paused
+            && self.learn_state.question_type() == Some(QuestionType::MultipleChoice)
+            && !self.learn_state.display_correct()
+    }
+
+    //Answers the current multiple choice question with the option labelled
+    //with the number key
+    fn submit_mcq_option(&mut self, key: glfw::Key) {
+        let Some(index) = number_key_index(key) else {
+            return;
+        };
+
+        if let Some(ans) = self.learn_state.mcq_ans.get(index).cloned() {
+            self.learn_state.submit(&ans);
+            self.answer.clear();
+        }
+    }
+
+    //Returns true if the current screen has a text box for typing answers
+    fn typing_answers(&self) -> bool {
+        matches!(
+            self.current_screen,
+            GameScreen::Game | GameScreen::Learn | GameScreen::Exam
+        )
+    }
+
+    //If `ch` completes a compose sequence with the previously typed character,
+    //returns the character that the sequence produces
+    fn compose(&mut self, ch: char) -> Option<char> {
+        let prev = self.last_typed.replace(ch)?;
+        if !self.settings.compose_enabled {
+            return None;
+        }
+        let table = self
+            .compose_tables
+            .iter()
+            .find(|table| table.language == self.settings.compose_language)?;
+        let composed = table.compose(prev, ch)?;
+        self.last_typed = Some(composed);
+        Some(composed)
     }
 
     pub fn process_events(&mut self, events: &EventHandler, gui_controller: &mut GuiController) {
@@ -184,8 +421,25 @@ impl Game {
                 WindowEvent::Size(w, h) => handle_window_resize(self, w, h),
                 WindowEvent::Key(glfw::Key::Enter, _, glfw::Action::Press, _)
                 | WindowEvent::Key(glfw::Key::KpEnter, _, glfw::Action::Press, _) => {
+                    self.last_typed = None;
                     if self.current_screen == GameScreen::Learn {
-                        self.learn_state.submit(&self.answer);
+                        if self.learn_paused {
+                            continue;
+                        }
+                        if self.learn_state.waiting_to_continue() {
+                            self.learn_state.continue_to_next();
+                            continue;
+                        }
+                        //Other question types are answered by clicking or with shortcuts
+                        let question_type = self.learn_state.question_type();
+                        if question_type == Some(QuestionType::FreeResponse) {
+                            self.learn_state.submit(&self.answer);
+                            self.answer.clear();
+                        }
+                        continue;
+                    }
+                    if self.current_screen == GameScreen::Exam {
+                        self.exam_state.submit(&self.answer);
                         self.answer.clear();
                         continue;
                     }
@@ -193,6 +447,86 @@ impl Game {
                     self.submit_answer();
                     continue;
                 }
+                WindowEvent::Key(key, _, glfw::Action::Press, mods)
+                    if mods.contains(glfw::Modifiers::Alt) =>
+                {
+                    //Insert a palette character at the cursor of the answer box
+                    if let Some(ch) = self.palette_shortcut(key) {
+                        let text = Event::Text(ch.to_string());
+                        gui_controller.input_state.input.events.push(text);
+                        self.skip_next_char = true;
+                        continue;
+                    }
+                }
+                //Do not type the number from an Alt+number shortcut
+                WindowEvent::Char(_) if self.skip_next_char => {
+                    self.skip_next_char = false;
+                    continue;
+                }
+                //Do not type the number of a multiple choice option
+                WindowEvent::Char(ch)
+                    if self.current_screen == GameScreen::Learn
+                        && self.learn_state.question_type()
+                            == Some(QuestionType::MultipleChoice)
+                        && ch.is_ascii_digit() =>
+                {
+                    continue;
+                }
+                WindowEvent::Char(ch) if self.typing_answers() => {
+                    //Replace the previously typed character with the composed one
+                    if let Some(composed) = self.compose(ch) {
+                        let events = &mut gui_controller.input_state.input.events;
+                        events.push(Event::Key {
+                            key: egui::Key::Backspace,
+                            physical_key: None,
+                            pressed: true,
+                            repeat: false,
+                            modifiers: Modifiers::NONE,
+                        });
+                        events.push(Event::Text(composed.to_string()));
+                        continue;
+                    }
+                }
+                //Use power-ups
+                WindowEvent::Key(key, _, glfw::Action::Press, _)
+                    if self.current_screen == GameScreen::Game && self.powerup_shortcut(key) =>
+                {
+                    continue;
+                }
+                //Pause or unpause learn mode
+                WindowEvent::Key(glfw::Key::Escape, _, glfw::Action::Press, _)
+                    if self.current_screen == GameScreen::Learn =>
+                {
+                    self.learn_paused = !self.learn_paused;
+                    continue;
+                }
+                //Answer multiple choice questions with the number keys
+                WindowEvent::Key(key, _, glfw::Action::Press, _)
+                    if self.answering_mcq() && number_key_index(key).is_some() =>
+                {
+                    self.submit_mcq_option(key);
+                    continue;
+                }
+                //Answer true/false questions with the arrow keys
+                WindowEvent::Key(
+                    key @ (glfw::Key::Left | glfw::Key::Right),
+                    _,
+                    glfw::Action::Press,
+                    _,
+                ) if self.current_screen == GameScreen::Learn
+                    && !self.learn_paused
+                    && self.learn_state.question_type() == Some(QuestionType::TrueFalse) =>
+                {
+                    self.learn_state.submit_true_false(key == glfw::Key::Left);
+                    continue;
+                }
+                //Moving the cursor breaks up a compose sequence
+                WindowEvent::Key(key, _, glfw::Action::Press | glfw::Action::Repeat, _)
+                    if moves_cursor(key) =>
+                {
+                    self.last_typed = None;
+                }
+                WindowEvent::MouseButton(_, glfw::Action::Press, _) => self.last_typed = None,
                 WindowEvent::Scroll(x, y) => {
                     let mouse_wheel = Event::MouseWheel {
                         unit: MouseWheelUnit::Line,
@@ -205,96 +539,53 @@ impl Game {
             }
             gui_controller.handle_window_event(event);
         }
+        //The character of a shortcut arrives with its key press, if it did
+        //not arrive then do not drop the next character that is typed
+        self.skip_next_char = false;
     }
 
     pub fn submit_answer(&mut self) {
-        //Ignore if game over
-        if self.game_over() || self.answer.is_empty() {
+        //The answers in a replay come from the recording
+        if self.playback.is_some() {
+            self.answer.clear();
             return;
         }
 
-        //Destroy asteroids
-        //Find the lowest asteroid
-        let mut index = None;
-        let mut lowest_y = 999.0;
-        let mut found_red = false;
-        for (i, asteroid) in self.asteroids.iter().enumerate() {
-            //ignore asteroids that are off-screen
-            if asteroid.above_top() {
-                continue;
-            }
-
-            if asteroid.is_red {
-                found_red = true;
-            }
-
-            if asteroid.flashcard.answer == self.answer && lowest_y > asteroid.sprite.y {
-                lowest_y = asteroid.sprite.y;
-                index = Some(i);
-            }
-        }
-
-        if let Some(index) = index {
-            self.asteroids[index].deleted = true;
-            self.asteroids[index].destroyed = true;
-            if self.asteroids[index].is_red {
-                //2 times as many points if it is red
-                self.score += 200 * self.level as u64;
-            } else {
-                self.score += 100 * self.level as u64;
-            }
-            if self.asteroids_until_next_level > 0 {
-                self.asteroids_until_next_level -= 1;
-            }
-        }
-
-        //lose helath if we enter something wrong and there is a red asteroid
-        //on the screen and destroy all red asteroids on the screen
-        if found_red && index.is_none() {
-            if self.health > 0 {
-                self.health -= 1;
-            }
-            if self.health > 0 {
-                self.damage_animation_timer = DAMAGE_ANIMATION_LENGTH;
-            }
+        if !self.game_over()
+            && !self.answer.is_empty()
+            && let Some(recording) = &mut self.recording
+        {
+            recording.record(self.step, &self.answer);
         }
-
+        self.sim.submit_answer(&self.answer);
         self.answer.clear();
     }
 
-    pub fn advance_to_next_level(&mut self) {
-        if self.game_over() {
-            return;
-        }
-
-        //If we are to advance ont the next level, delete any asteroids that
-        //are above the top of the screen
-        if self.asteroids_until_next_level == 0 {
-            self.asteroids = self
-                .asteroids
-                .iter()
-                .filter(|asteroid| !asteroid.above_top())
-                .cloned()
-                .collect();
-        }
-
-        //Count any non-destroyed asteroids
-        let mut count = 0;
-        for asteroid in &self.asteroids {
-            if asteroid.destroyed || asteroid.deleted {
-                continue;
-            }
-            count += 1;
+    //Uses the power-up for an F1 - F4 hotkey, returns true if the key was a
+    //power-up hotkey
+    fn powerup_shortcut(&mut self, key: glfw::Key) -> bool {
+        let index = match key {
+            glfw::Key::F1 => 0,
+            glfw::Key::F2 => 1,
+            glfw::Key::F3 => 2,
+            glfw::Key::F4 => 3,
+            _ => return false,
+        };
+
+        //The power-ups in a replay come from the recording
+        let powerup = PowerUp::ALL[index];
+        if self.playback.is_none()
+            && self.sim.use_powerup(powerup)
+            && let Some(recording) = &mut self.recording
+        {
+            recording.record_powerup(self.step, powerup);
         }
+        true
+    }
 
-        //Check if we advanced to the next level
-        if self.asteroids_until_next_level == 0 && count == 0 {
-            self.level += 1;
-            self.asteroids_until_next_level = calculate_asteroids_until_next(self.level);
-            self.spawn_interval = calculate_spawn_interval(self.level);
-            self.levelup_animation_timer = LEVELUP_ANIMATION_LENGTH;
-            self.audio.play("levelup");
-        }
+    //Returns if its game over
+    pub fn game_over(&self) -> bool {
+        self.sim.game_over()
     }
 
     pub fn levelup_animation_perc(&self) -> f32 {
@@ -4,6 +4,7 @@ pub mod shaders;
 pub mod textures;
 
 use super::Game;
+use crate::compose;
 use egui_backend::egui::{FontData, FontDefinitions, FontFamily};
 use egui_gl_glfw as egui_backend;
 use std::{fs::File, io::Read};
@@ -68,5 +69,6 @@ impl Game {
         self.shaders.load_shaders("assets/shaders.impfile");
         self.textures.load_textures("assets/textures.impfile");
         self.audio.load_audio("assets/audio.impfile");
+        self.compose_tables = compose::load_compose_tables("assets/compose.impfile");
     }
 }
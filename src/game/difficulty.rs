@@ -0,0 +1,257 @@
+/*
+ * Difficulty of the asteroid game: how much health the player has, how
+ * quickly asteroids spawn and fall, how long levels are, how often asteroids
+ * are red, how they move and how many points they are worth. The presets are
+ * loaded from `assets/difficulty.impfile` and the player can also make a
+ * custom one
+ * */
+
+mod tests;
+
+use super::{draw::CANVAS_H, movement::Movement};
+use crate::impfile::{self, Entry};
+
+pub const CUSTOM_DIFFICULTY: &str = "custom";
+pub const DEFAULT_DIFFICULTY: &str = "normal";
+//Answers longer than this make asteroids fall slower, down to
+//`MIN_ANSWER_SPEED` times their normal speed
+const SHORT_ANSWER_LENGTH: usize = 6;
+const MIN_ANSWER_SPEED: f32 = 0.5;
+
+//Parses a comma separated list of numbers, invalid numbers are skipped
+fn parse_list<T: std::str::FromStr>(list: &str) -> Vec<T> {
+    list.split(',')
+        .filter_map(|value| value.trim().parse().ok())
+        .collect()
+}
+
+fn list_to_string<T: ToString>(list: &[T]) -> String {
+    list.iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+//Returns the value for `level` (starting at 1), levels past the end of the
+//list use the last value
+fn value_for_level<T: Copy>(list: &[T], level: u32, default: T) -> T {
+    let index = (level.max(1) - 1) as usize;
+    list.get(index).or(list.last()).copied().unwrap_or(default)
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Difficulty {
+    pub health: u32,
+    //Time between asteroids spawning (in seconds) before the first level, it
+    //is multiplied by `spawn_interval_scale` every level
+    pub spawn_interval: f32,
+    pub spawn_interval_scale: f32,
+    pub min_spawn_interval: f32,
+    //Time it takes an asteroid to fall down the screen (in seconds) on level
+    //1, this decreases by `fall_time_step` every level
+    pub fall_time: f32,
+    pub fall_time_step: f32,
+    pub min_fall_time: f32,
+    //Number of asteroids to destroy to finish each level
+    pub level_lengths: Vec<u32>,
+    //Each asteroid on a level has a 1 in N chance of being red (0 = never)
+    pub red_chances: Vec<u32>,
+    //Level from which a third asteroid can spawn at a time
+    pub third_asteroid_level: u32,
+    //Level from which each movement in `Movement::SPECIAL` appears (0 = never),
+    //in the same order as `Movement::SPECIAL`
+    pub movement_levels: [u32; Movement::SPECIAL.len()],
+    //Each asteroid has a 1 in N chance of moving in one of the unlocked ways
+    //instead of falling straight down (0 = never)
+    pub movement_chance: u32,
+    //How much slower an asteroid falls for each letter its answer has past
+    //`SHORT_ANSWER_LENGTH`
+    pub slowdown_per_letter: f32,
+    //Points for destroying an asteroid, multiplied by the level
+    pub points: u64,
+    pub red_points: u64,
+}
+
+impl Difficulty {
+    //The normal difficulty
+    pub fn new() -> Self {
+        Self {
+            health: 5,
+            spawn_interval: 8.0,
+            spawn_interval_scale: 0.85,
+            min_spawn_interval: 2.0,
+            fall_time: 25.0,
+            fall_time_step: 1.0,
+            min_fall_time: 5.0,
+            level_lengths: vec![
+                5, 7, 10, 10, 10, 15, 15, 15, 20, 20, 20, 20, 25, 25, 25, 30, 30, 30, 40,
+            ],
+            red_chances: vec![0, 7, 10, 10, 10, 8, 8, 8, 6, 6, 6, 6, 6, 6, 6, 5],
+            third_asteroid_level: 6,
+            //Diagonal, zig-zag, comet, heavy
+            movement_levels: [3, 6, 8, 4],
+            movement_chance: 3,
+            slowdown_per_letter: 0.04,
+            points: 100,
+            red_points: 200,
+        }
+    }
+
+    //Values that are missing from the entry are taken from the normal
+    //difficulty
+    pub fn from_entry(entry: &Entry) -> Self {
+        let default = Self::new();
+        let level_lengths = parse_list(&entry.get_var("level_lengths"));
+        let red_chances = parse_list(&entry.get_var("red_chances"));
+        let movement_levels = std::array::from_fn(|i| {
+            let movement = Movement::SPECIAL[i];
+            entry
+                .get_var(&format!("{}_level", movement.id()))
+                .parse()
+                .unwrap_or(default.movement_levels[i])
+        });
+        Self {
+            health: entry
+                .get_var("health")
+                .parse()
+                .unwrap_or(default.health)
+                .max(1),
+            spawn_interval: entry
+                .get_var("spawn_interval")
+                .parse()
+                .unwrap_or(default.spawn_interval),
+            spawn_interval_scale: entry
+                .get_var("spawn_interval_scale")
+                .parse()
+                .unwrap_or(default.spawn_interval_scale),
+            min_spawn_interval: entry
+                .get_var("min_spawn_interval")
+                .parse()
+                .unwrap_or(default.min_spawn_interval),
+            fall_time: entry
+                .get_var("fall_time")
+                .parse()
+                .unwrap_or(default.fall_time),
+            fall_time_step: entry
+                .get_var("fall_time_step")
+                .parse()
+                .unwrap_or(default.fall_time_step),
+            min_fall_time: entry
+                .get_var("min_fall_time")
+                .parse()
+                .unwrap_or(default.min_fall_time),
+            level_lengths: if level_lengths.is_empty() {
+                default.level_lengths
+            } else {
+                level_lengths
+            },
+            red_chances: if red_chances.is_empty() {
+                default.red_chances
+            } else {
+                red_chances
+            },
+            third_asteroid_level: entry
+                .get_var("third_asteroid_level")
+                .parse()
+                .unwrap_or(default.third_asteroid_level),
+            movement_levels,
+            movement_chance: entry
+                .get_var("movement_chance")
+                .parse()
+                .unwrap_or(default.movement_chance),
+            slowdown_per_letter: entry
+                .get_var("slowdown_per_letter")
+                .parse()
+                .unwrap_or(default.slowdown_per_letter),
+            points: entry.get_var("points").parse().unwrap_or(default.points),
+            red_points: entry
+                .get_var("red_points")
+                .parse()
+                .unwrap_or(default.red_points),
+        }
+    }
+
+    pub fn to_entry(&self, name: &str) -> Entry {
+        let mut entry = Entry::new(name);
+        entry.add_integer("health", self.health as i64);
+        entry.add_float("spawn_interval", self.spawn_interval);
+        entry.add_float("spawn_interval_scale", self.spawn_interval_scale);
+        entry.add_float("min_spawn_interval", self.min_spawn_interval);
+        entry.add_float("fall_time", self.fall_time);
+        entry.add_float("fall_time_step", self.fall_time_step);
+        entry.add_float("min_fall_time", self.min_fall_time);
+        entry.add_string("level_lengths", &list_to_string(&self.level_lengths));
+        entry.add_string("red_chances", &list_to_string(&self.red_chances));
+        entry.add_integer("third_asteroid_level", self.third_asteroid_level as i64);
+        for (movement, level) in Movement::SPECIAL.iter().zip(&self.movement_levels) {
+            let name = format!("{}_level", movement.id());
+            entry.add_integer(&name, *level as i64);
+        }
+        entry.add_integer("movement_chance", self.movement_chance as i64);
+        entry.add_float("slowdown_per_letter", self.slowdown_per_letter);
+        entry.add_string("points", &self.points.to_string());
+        entry.add_string("red_points", &self.red_points.to_string());
+        entry
+    }
+
+    //Calculates how many asteroids are needed to advance to the next level
+    //Pass in the current level
+    pub fn asteroids_until_next(&self, level: u32) -> u32 {
+        value_for_level(&self.level_lengths, level, 1).max(1)
+    }
+
+    pub fn spawn_interval(&self, level: u32) -> f32 {
+        (self.spawn_interval * self.spawn_interval_scale.powi(level as i32))
+            .max(self.min_spawn_interval)
+    }
+
+    pub fn asteroid_speed(&self, level: u32) -> f32 {
+        let fall_time = self.fall_time - (level - 1) as f32 * self.fall_time_step;
+        CANVAS_H / fall_time.max(self.min_fall_time).max(1.0)
+    }
+
+    pub fn is_red(&self, rand_value: u32, level: u32) -> bool {
+        let chance = value_for_level(&self.red_chances, level, 0);
+        chance != 0 && rand_value.is_multiple_of(chance)
+    }
+
+    //Returns the movements that asteroids can have on `level` besides
+    //falling straight down
+    pub fn movements(&self, level: u32) -> Vec<Movement> {
+        Movement::SPECIAL
+            .iter()
+            .zip(&self.movement_levels)
+            .filter(|(_, unlock_level)| **unlock_level != 0 && **unlock_level <= level)
+            .map(|(movement, _)| *movement)
+            .collect()
+    }
+
+    //Multiplies the speed of an asteroid so that long answers get more time
+    pub fn answer_speed(&self, answer: &str) -> f32 {
+        let extra_letters = answer.chars().count().saturating_sub(SHORT_ANSWER_LENGTH);
+        (1.0 - extra_letters as f32 * self.slowdown_per_letter).max(MIN_ANSWER_SPEED)
+    }
+
+    pub fn points(&self, red: bool, level: u32) -> u64 {
+        if red {
+            self.red_points * level as u64
+        } else {
+            self.points * level as u64
+        }
+    }
+}
+
+//Loads the difficulty presets as (name, difficulty), the normal difficulty is
+//used if there are none
+pub fn load_presets(path: &str) -> Vec<(String, Difficulty)> {
+    let presets: Vec<(String, Difficulty)> = impfile::find_impfile(path)
+        .iter()
+        .filter(|entry| entry.get_name() != CUSTOM_DIFFICULTY)
+        .map(|entry| (entry.get_name(), Difficulty::from_entry(entry)))
+        .collect();
+    if presets.is_empty() {
+        eprintln!("No difficulty presets found in {path}");
+        return vec![(DEFAULT_DIFFICULTY.to_string(), Difficulty::new())];
+    }
+    presets
+}
@@ -0,0 +1,57 @@
+#[cfg(test)]
+use super::*;
+
+#[test]
+fn test_later_levels_use_last_value() {
+    let difficulty = Difficulty::new();
+    assert_eq!(difficulty.asteroids_until_next(1), 5);
+    assert_eq!(difficulty.asteroids_until_next(19), 40);
+    assert_eq!(difficulty.asteroids_until_next(100), 40);
+}
+
+#[test]
+fn test_never_red() {
+    let difficulty = Difficulty::new();
+    for value in 0..100 {
+        assert!(!difficulty.is_red(value, 1));
+    }
+    assert!(difficulty.is_red(0, 2));
+}
+
+#[test]
+fn test_entry_round_trip() {
+    let mut difficulty = Difficulty::new();
+    difficulty.health = 3;
+    difficulty.level_lengths = vec![4, 8];
+    difficulty.red_chances = vec![0, 3];
+    difficulty.movement_levels = [0, 2, 5, 9];
+    let loaded = Difficulty::from_entry(&difficulty.to_entry("custom"));
+    assert!(loaded == difficulty);
+}
+
+#[test]
+fn test_missing_values_use_normal() {
+    let entry = Entry::new("custom");
+    assert!(Difficulty::from_entry(&entry) == Difficulty::new());
+}
+
+#[test]
+fn test_movements_unlock_by_level() {
+    let difficulty = Difficulty::new();
+    assert!(difficulty.movements(1).is_empty());
+    assert_eq!(difficulty.movements(3), vec![Movement::Diagonal]);
+    assert_eq!(
+        difficulty.movements(4),
+        vec![Movement::Diagonal, Movement::Heavy]
+    );
+    //Always in the same order so the same seed picks the same movements
+    assert_eq!(difficulty.movements(100), Movement::SPECIAL);
+}
+
+#[test]
+fn test_long_answers_fall_slower() {
+    let difficulty = Difficulty::new();
+    assert_eq!(difficulty.answer_speed("abc"), 1.0);
+    assert!(difficulty.answer_speed("abcdefghij") < 1.0);
+    assert_eq!(difficulty.answer_speed(&"a".repeat(100)), MIN_ANSWER_SPEED);
+}
@@ -5,12 +5,18 @@ use egui_gl_glfw::egui::vec2;
 use super::{
     Game,
     assets::models::{draw_elements, draw_elements_instanced},
+    movement::Movement,
+    powerup::PowerUp,
     update::EXPLOSION_LIFETIME,
 };
 
 pub const CANVAS_W: f32 = 960.0;
 pub const CANVAS_H: f32 = 540.0;
 const ASPECT: f32 = CANVAS_W / CANVAS_H;
+//Position of the first power-up icon in the hud and the space between icons
+pub const POWERUP_ICON_X: f32 = 24.0;
+pub const POWERUP_ICON_Y: f32 = 146.0;
+pub const POWERUP_ICON_SPACING: f32 = 72.0;
 
 pub fn calculate_screen_mat(w: i32, h: i32) -> Matrix4<f32> {
     let (w, h) = (w as f32, h as f32);
@@ -90,9 +96,13 @@ fn draw_planet(gamestate: &Game, shader: &ShaderProgram) {
 fn draw_asteroids(gamestate: &Game, shader: &ShaderProgram) {
     gamestate.textures.bind("asteroid");
     let quad = gamestate.models.bind("quad2d");
-    for asteroid in &gamestate.asteroids {
+    for asteroid in &gamestate.sim.asteroids {
         if asteroid.is_red {
             shader.uniform_vec4f("tint", 1.0, 0.2, 0.2, 1.0);
+        } else if asteroid.movement == Movement::Comet {
+            shader.uniform_vec4f("tint", 1.0, 0.75, 0.4, 1.0);
+        } else if asteroid.movement == Movement::Heavy {
+            shader.uniform_vec4f("tint", 0.6, 0.6, 0.65, 1.0);
         } else {
             shader.uniform_vec4f("tint", 1.0, 1.0, 1.0, 1.0);
         }
@@ -108,10 +118,48 @@ fn draw_asteroids(gamestate: &Game, shader: &ShaderProgram) {
     shader.uniform_vec4f("tint", 1.0, 1.0, 1.0, 1.0);
 }
 
+//Each answer a boss takes adds a crack to it
+fn draw_boss_cracks(gamestate: &Game, shader: &ShaderProgram) {
+    gamestate.textures.bind("crack");
+    let quad = gamestate.models.bind("quad2d");
+    for asteroid in &gamestate.sim.asteroids {
+        let cracks = asteroid.max_hit_points - asteroid.hit_points;
+        for i in 0..cracks {
+            let w = asteroid.sprite.width;
+            let h = asteroid.sprite.height;
+            let translate = Vector3::new(asteroid.sprite.x, asteroid.sprite.y, 0.0);
+            //Rotate each crack differently so they do not overlap
+            let rotation = asteroid.rotation + i as f32 * 2.4;
+            let transform = Matrix4::from_translation(translate)
+                * Matrix4::from_angle_z(Rad(rotation))
+                * Matrix4::from_nonuniform_scale(w, h, 1.0);
+            shader.uniform_matrix4f("transform", &transform);
+            draw_elements(quad.clone());
+        }
+    }
+}
+
+//Display the icon of the power-up an asteroid carries on its corner
+fn draw_asteroid_powerups(gamestate: &Game, shader: &ShaderProgram) {
+    let quad = gamestate.models.bind("quad2d");
+    for asteroid in &gamestate.sim.asteroids {
+        let Some(powerup) = asteroid.powerup else {
+            continue;
+        };
+        let offset = asteroid.sprite.width * 0.35;
+        let translate = Vector3::new(asteroid.sprite.x + offset, asteroid.sprite.y + offset, 0.0);
+        let transform =
+            Matrix4::from_translation(translate) * Matrix4::from_nonuniform_scale(32.0, 32.0, 1.0);
+        gamestate.textures.bind(powerup.icon());
+        shader.uniform_matrix4f("transform", &transform);
+        draw_elements(quad.clone());
+    }
+}
+
 fn draw_asteroids_flame(gamestate: &Game, shader: &ShaderProgram) {
     gamestate.textures.bind("fire");
     let quad = gamestate.models.bind("quad2d");
-    for asteroid in &gamestate.asteroids {
+    for asteroid in &gamestate.sim.asteroids {
         let translate = Vector3::new(asteroid.sprite.x, asteroid.sprite.y, 0.0);
         let perc = (-(-CANVAS_H / 2.0 - asteroid.sprite.y) * 2.0 / CANVAS_H).clamp(0.0, 1.0);
         shader.uniform_float("alpha", perc);
@@ -194,6 +242,8 @@ impl Game {
         //Draw asteroids
         shader.use_program();
         draw_asteroids(self, &shader);
+        draw_boss_cracks(self, &shader);
+        draw_asteroid_powerups(self, &shader);
 
         //Draw explosions
         let explosionshader = self.shaders.use_program("explosionshader");
@@ -206,6 +256,23 @@ impl Game {
 
         //Display heart icon (for gui)
         display_icon(self, &shader, "hearticon", 24.0, 26.0, 24.0, 24.0);
+        //Display power-up icons, active power-ups are yellow and power-ups
+        //the player does not have are faded
+        for (i, powerup) in PowerUp::ALL.iter().enumerate() {
+            let active = match powerup {
+                PowerUp::Shield => self.sim.shield_active,
+                PowerUp::SlowTime => self.sim.slow_timer > 0.0,
+                PowerUp::Bomb | PowerUp::ExtraHeart => false,
+            };
+            if active {
+                shader.uniform_vec4f("tint", 1.0, 1.0, 0.3, 1.0);
+            } else if self.sim.powerup_count(*powerup) == 0 {
+                shader.uniform_vec4f("tint", 1.0, 1.0, 1.0, 0.3);
+            }
+            let x = POWERUP_ICON_X + i as f32 * POWERUP_ICON_SPACING;
+            display_icon(self, &shader, powerup.icon(), x, POWERUP_ICON_Y, 24.0, 24.0);
+            shader.uniform_vec4f("tint", 1.0, 1.0, 1.0, 1.0);
+        }
 
         //Unbind textures
         unsafe {
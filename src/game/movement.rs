@@ -0,0 +1,76 @@
+/*
+ * How asteroids move down the screen, every asteroid falls but some also
+ * drift sideways or change speed as they fall
+ * */
+
+//Sideways speeds are relative to how fast the asteroid falls
+const DIAGONAL_SPEED: f32 = 0.35;
+const ZIGZAG_SPEED: f32 = 0.6;
+//How long a zig-zagging asteroid moves in one direction (in seconds)
+const ZIGZAG_TIME: f32 = 1.5;
+//Comets start slow and speed up as they fall
+const COMET_START_SPEED: f32 = 0.5;
+const COMET_ACCELERATION: f32 = 0.15; //Per second
+const COMET_MAX_SPEED: f32 = 2.0;
+//Heavy rocks are big and slow
+const HEAVY_SPEED: f32 = 0.6;
+const HEAVY_SIZE: f32 = 1.25;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Movement {
+    Straight,
+    Diagonal,
+    ZigZag,
+    Comet,
+    Heavy,
+}
+
+impl Movement {
+    //Movements that are unlocked by level in the difficulty config
+    pub const SPECIAL: [Self; 4] = [Self::Diagonal, Self::ZigZag, Self::Comet, Self::Heavy];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Straight => "straight",
+            Self::Diagonal => "diagonal",
+            Self::ZigZag => "zigzag",
+            Self::Comet => "comet",
+            Self::Heavy => "heavy",
+        }
+    }
+
+    //Multiplies the speed an asteroid falls at, `age` is how long the
+    //asteroid has existed (in seconds)
+    pub fn speed(&self, age: f32) -> f32 {
+        match self {
+            Self::Comet => (COMET_START_SPEED + COMET_ACCELERATION * age).min(COMET_MAX_SPEED),
+            Self::Heavy => HEAVY_SPEED,
+            Self::Straight | Self::Diagonal | Self::ZigZag => 1.0,
+        }
+    }
+
+    //Sideways speed when moving to the right, `age` is how long the asteroid
+    //has existed (in seconds)
+    pub fn sideways_speed(&self, age: f32) -> f32 {
+        match self {
+            Self::Diagonal => DIAGONAL_SPEED,
+            Self::ZigZag => {
+                //Switch direction every `ZIGZAG_TIME` seconds
+                if ((age / ZIGZAG_TIME) as u32).is_multiple_of(2) {
+                    ZIGZAG_SPEED
+                } else {
+                    -ZIGZAG_SPEED
+                }
+            }
+            Self::Straight | Self::Comet | Self::Heavy => 0.0,
+        }
+    }
+
+    //Multiplies the size of an asteroid
+    pub fn size(&self) -> f32 {
+        match self {
+            Self::Heavy => HEAVY_SIZE,
+            Self::Straight | Self::Diagonal | Self::ZigZag | Self::Comet => 1.0,
+        }
+    }
+}
@@ -0,0 +1,53 @@
+/*
+ * Power-ups are earned by answering several asteroids in a row correctly or
+ * by destroying an asteroid that carries one, they are used with hotkeys
+ * */
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PowerUp {
+    //Absorbs the next asteroid that hits the planet
+    Shield,
+    //Asteroids fall at half speed for a few seconds
+    SlowTime,
+    //Destroys every asteroid on the screen
+    Bomb,
+    //Restores one hit point
+    ExtraHeart,
+}
+
+impl PowerUp {
+    //In the order of their hotkeys (F1 - F4)
+    pub const ALL: [Self; 4] = [Self::Shield, Self::SlowTime, Self::Bomb, Self::ExtraHeart];
+
+    pub fn index(&self) -> usize {
+        match self {
+            Self::Shield => 0,
+            Self::SlowTime => 1,
+            Self::Bomb => 2,
+            Self::ExtraHeart => 3,
+        }
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Shield => "shield",
+            Self::SlowTime => "slow",
+            Self::Bomb => "bomb",
+            Self::ExtraHeart => "heart",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.iter().find(|powerup| powerup.id() == id).copied()
+    }
+
+    //Name of the texture used for the icon
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Self::Shield => "shieldicon",
+            Self::SlowTime => "slowicon",
+            Self::Bomb => "bombicon",
+            Self::ExtraHeart => "hearticon",
+        }
+    }
+}
@@ -0,0 +1,202 @@
+/*
+ * Picks which card appears on the next asteroid, cards can be picked
+ * uniformly, weighted towards cards the player struggles with, or from a
+ * shuffle bag so that every card appears before any card repeats
+ * */
+
+mod tests;
+
+use crate::{
+    flashcards::Flashcard,
+    progress::{self, Progress},
+};
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+//Extra weight for each time a card reached the planet this game
+const MISS_WEIGHT: f32 = 2.0;
+//Answers that take longer than this (in seconds) count as slow
+const SLOW_ANSWER_TIME: f32 = 10.0;
+const MAX_SLOW_WEIGHT: f32 = 2.0;
+//Extra weight for cards that have never been studied
+const NEW_CARD_WEIGHT: f32 = 1.5;
+//Extra weight for each time a studied card was forgotten
+const LAPSE_WEIGHT: f32 = 0.5;
+//Cards in the same group are related unless the group is bigger than this,
+//large groups are usually a whole set of unrelated cards
+const MAX_RELATED_GROUP_SIZE: usize = 12;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum CardSelection {
+    //Every card is equally likely
+    Uniform,
+    //Missed, slow and new cards are more likely
+    Weighted,
+    //Every card appears once before any card repeats
+    ShuffleBag,
+}
+
+impl CardSelection {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Uniform => "uniform",
+            Self::Weighted => "weighted",
+            Self::ShuffleBag => "shuffle",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Self {
+        match id {
+            "weighted" => Self::Weighted,
+            "shuffle" => Self::ShuffleBag,
+            _ => Self::Uniform,
+        }
+    }
+}
+
+//What happened to a card in the current game
+#[derive(Clone, Default)]
+struct CardHistory {
+    misses: u32,
+    answers: u32,
+    //Total time the card was on the screen before it was answered
+    answer_time: f32,
+}
+
+pub struct CardSelector {
+    pub mode: CardSelection,
+    cards: Vec<Flashcard>,
+    //Weight of each card from the saved progress
+    priors: Vec<f32>,
+    history: Vec<CardHistory>,
+    //Indices of the cards left in the shuffle bag
+    bag: Vec<usize>,
+}
+
+impl CardSelector {
+    pub fn new(cards: &[Flashcard]) -> Self {
+        Self {
+            mode: CardSelection::Uniform,
+            cards: cards.to_vec(),
+            priors: vec![0.0; cards.len()],
+            history: vec![CardHistory::default(); cards.len()],
+            bag: vec![],
+        }
+    }
+
+    //Clears the history of the current game
+    pub fn reset(&mut self) {
+        self.history = vec![CardHistory::default(); self.cards.len()];
+        self.bag.clear();
+    }
+
+    //Sets the weights from saved progress, cards that were never studied and
+    //cards that were often forgotten are picked more often
+    pub fn set_progress(&mut self, progress: &Progress) {
+        self.priors = self
+            .cards
+            .iter()
+            .map(|card| match progress.get(card) {
+                Some(card_progress) => {
+                    let ease_weight = (progress::DEFAULT_EASE - card_progress.ease).max(0.0);
+                    card_progress.lapses as f32 * LAPSE_WEIGHT + ease_weight
+                }
+                None => NEW_CARD_WEIGHT,
+            })
+            .collect();
+    }
+
+    pub fn priors(&self) -> &[f32] {
+        &self.priors
+    }
+
+    //Used when playing back a replay so that cards are picked the same way
+    pub fn set_priors(&mut self, priors: &[f32]) {
+        if priors.len() == self.cards.len() {
+            self.priors = priors.to_vec();
+        }
+    }
+
+    fn index_of(&self, card: &Flashcard) -> Option<usize> {
+        self.cards.iter().position(|other| other == card)
+    }
+
+    pub fn record_miss(&mut self, card: &Flashcard) {
+        if let Some(i) = self.index_of(card) {
+            self.history[i].misses += 1;
+        }
+    }
+
+    //`time` is how long the card was on the screen before it was answered
+    pub fn record_answer(&mut self, card: &Flashcard, time: f32) {
+        if let Some(i) = self.index_of(card) {
+            self.history[i].answers += 1;
+            self.history[i].answer_time += time;
+        }
+    }
+
+    //Returns the other cards in the same group as `card`
+    pub fn related(&self, card: &Flashcard) -> Vec<Flashcard> {
+        if card.group.is_empty() {
+            return vec![];
+        }
+        let group: Vec<&Flashcard> = self
+            .cards
+            .iter()
+            .filter(|other| other.group == card.group)
+            .collect();
+        if group.len() > MAX_RELATED_GROUP_SIZE {
+            return vec![];
+        }
+        group
+            .into_iter()
+            .filter(|other| *other != card)
+            .cloned()
+            .collect()
+    }
+
+    fn weight(&self, i: usize) -> f32 {
+        let history = &self.history[i];
+        let slow_weight = if history.answers > 0 {
+            let average_time = history.answer_time / history.answers as f32;
+            (average_time / SLOW_ANSWER_TIME).min(MAX_SLOW_WEIGHT)
+        } else {
+            0.0
+        };
+        1.0 + self.priors[i] + history.misses as f32 * MISS_WEIGHT + slow_weight
+    }
+
+    fn pick_weighted(&self, rng: &mut StdRng) -> usize {
+        let total: f32 = (0..self.cards.len()).map(|i| self.weight(i)).sum();
+        let mut target = rng.random::<f32>() * total;
+        for i in 0..self.cards.len() {
+            target -= self.weight(i);
+            if target < 0.0 {
+                return i;
+            }
+        }
+        self.cards.len() - 1
+    }
+
+    fn pick_from_bag(&mut self, rng: &mut StdRng) -> usize {
+        if self.bag.is_empty() {
+            self.bag = (0..self.cards.len()).collect();
+            self.bag.shuffle(rng);
+        }
+        self.bag.pop().unwrap_or(0)
+    }
+
+    pub fn pick(&mut self, rng: &mut StdRng) -> Flashcard {
+        if self.cards.is_empty() {
+            return Flashcard::none();
+        }
+
+        let i = match self.mode {
+            CardSelection::Uniform => rng.random_range(0..self.cards.len()),
+            CardSelection::Weighted => self.pick_weighted(rng),
+            CardSelection::ShuffleBag => self.pick_from_bag(rng),
+        };
+        self.cards[i].clone()
+    }
+}
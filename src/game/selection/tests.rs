@@ -0,0 +1,76 @@
+#[cfg(test)]
+use super::*;
+#[cfg(test)]
+use rand::SeedableRng;
+
+#[cfg(test)]
+fn test_cards() -> Vec<Flashcard> {
+    vec![
+        Flashcard::new("1 + 1", "2", "math"),
+        Flashcard::new("2 + 2", "4", "math"),
+        Flashcard::new("3 + 3", "6", "math"),
+        Flashcard::new("4 + 4", "8", "math"),
+    ]
+}
+
+#[test]
+fn test_shuffle_bag_picks_every_card() {
+    let cards = test_cards();
+    let mut selector = CardSelector::new(&cards);
+    selector.mode = CardSelection::ShuffleBag;
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..3 {
+        let picked: Vec<Flashcard> = (0..cards.len()).map(|_| selector.pick(&mut rng)).collect();
+        for card in &cards {
+            assert!(picked.contains(card));
+        }
+    }
+}
+
+#[test]
+fn test_weighted_prefers_missed_cards() {
+    let cards = test_cards();
+    let mut selector = CardSelector::new(&cards);
+    selector.mode = CardSelection::Weighted;
+    for _ in 0..5 {
+        selector.record_miss(&cards[0]);
+    }
+    let mut rng = StdRng::seed_from_u64(0);
+    let missed = (0..1000)
+        .filter(|_| selector.pick(&mut rng) == cards[0])
+        .count();
+    //The missed card has weight 11 out of a total of 14
+    assert!(missed > 600);
+}
+
+#[test]
+fn test_reset_clears_history() {
+    let cards = test_cards();
+    let mut selector = CardSelector::new(&cards);
+    selector.record_miss(&cards[0]);
+    selector.record_answer(&cards[1], 30.0);
+    selector.reset();
+    for i in 0..cards.len() {
+        assert_eq!(selector.weight(i), 1.0);
+    }
+}
+
+#[test]
+fn test_related_cards() {
+    let mut cards = test_cards();
+    cards.push(Flashcard::new("io (avere)", "ho", "avere"));
+    cards.push(Flashcard::new("tu (avere)", "hai", "avere"));
+    let selector = CardSelector::new(&cards);
+    let related = selector.related(&cards[4]);
+    assert_eq!(related.len(), 1);
+    assert!(related[0] == cards[5]);
+}
+
+#[test]
+fn test_large_group_is_not_related() {
+    let cards: Vec<Flashcard> = (0..=MAX_RELATED_GROUP_SIZE)
+        .map(|i| Flashcard::new(&format!("{i} + {i}"), &(i * 2).to_string(), "math"))
+        .collect();
+    let selector = CardSelector::new(&cards);
+    assert!(selector.related(&cards[0]).is_empty());
+}
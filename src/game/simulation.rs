@@ -0,0 +1,660 @@
+/*
+ * The asteroid game without any graphics or audio: spawning asteroids,
+ * scoring, levels, health and the log of missed asteroids. The simulation
+ * reports what happened as events that the renderer and audio consume
+ * */
+
+mod tests;
+
+use super::{
+    difficulty::Difficulty,
+    draw::{CANVAS_H, CANVAS_W},
+    movement::Movement,
+    powerup::PowerUp,
+    selection::CardSelector,
+    sprite::Asteroid,
+};
+use crate::{flashcards::Flashcard, log::LogItem};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::VecDeque;
+
+const ASTEROID_SIZE: f32 = 80.0;
+pub const MAX_LOG_LEN: usize = 16;
+//Number of correct answers in a row needed to raise the score multiplier
+pub const STREAK_PER_MULTIPLIER: u32 = 5;
+pub const MAX_MULTIPLIER: u32 = 4;
+//Asteroids destroyed with more than this fraction of their fall left earn a
+//speed bonus of up to `MAX_SPEED_BONUS` times their points
+const FAST_ANSWER_TIME_LEFT: f32 = 0.5;
+const MAX_SPEED_BONUS: f32 = 0.5;
+//A power-up is earned every this many correct answers in a row
+pub const POWERUP_STREAK: u32 = 10;
+//Each asteroid that is not red has a 1 in N chance of carrying a power-up
+const POWERUP_ASTEROID_CHANCE: u32 = 25;
+//The most of each power-up the player can hold
+pub const MAX_POWERUPS: u32 = 3;
+//How long slow time lasts (in seconds) and how much it slows asteroids down
+pub const SLOW_TIME_LENGTH: f32 = 5.0;
+const SLOW_TIME_SPEED: f32 = 0.5;
+//A boss asteroid appears at the end of each level, it is bigger and slower
+//than other asteroids and needs several answers to destroy
+const BOSS_SIZE: f32 = ASTEROID_SIZE * 2.0;
+pub const BOSS_MIN_CARDS: usize = 3;
+pub const BOSS_MAX_CARDS: usize = 5;
+const BOSS_SPEED: f32 = 0.5;
+//Every wrong answer makes a boss on the screen fall faster
+const BOSS_SPEEDUP: f32 = 0.15;
+const BOSS_MAX_SPEED: f32 = 1.5;
+//An asteroid whose card has related cards has a 1 in N chance of being a
+//big asteroid that splits into fragments with the related cards
+const SPLIT_CHANCE: u32 = 6;
+const SPLITTER_SIZE: f32 = ASTEROID_SIZE * 1.4;
+pub const MIN_FRAGMENTS: usize = 2;
+pub const MAX_FRAGMENTS: usize = 3;
+const FRAGMENT_SIZE: f32 = ASTEROID_SIZE * 0.75;
+//Fragments fall faster than other asteroids and fly apart sideways
+const FRAGMENT_SPEED: f32 = 1.6;
+const FRAGMENT_SPREAD: f32 = 0.6;
+//How far ahead (in seconds) the path of a new asteroid is checked for
+//overlaps with other asteroids
+const PATH_CHECK_TIME: f32 = 8.0;
+const PATH_CHECK_STEP: f32 = 0.25;
+//Approximate width of a character in the question of an asteroid
+const LABEL_CHAR_WIDTH: f32 = 10.0;
+
+//Returns the bonus for destroying an asteroid worth `points` with
+//`time_left` (0.0 - 1.0) of its fall time left
+fn speed_bonus(points: u64, time_left: f32) -> u64 {
+    if time_left <= FAST_ANSWER_TIME_LEFT {
+        return 0;
+    }
+    let fast = (time_left - FAST_ANSWER_TIME_LEFT) / (1.0 - FAST_ANSWER_TIME_LEFT);
+    (points as f32 * MAX_SPEED_BONUS * fast.min(1.0)) as u64
+}
+
+//Width of an asteroid or of its question, whichever is wider
+fn label_width(asteroid: &Asteroid) -> f32 {
+    let text_width = asteroid.flashcard.question.chars().count() as f32 * LABEL_CHAR_WIDTH;
+    text_width.max(asteroid.sprite.width)
+}
+
+fn overlaps(asteroid: &Asteroid, asteroid2: &Asteroid) -> bool {
+    let dx = (asteroid2.sprite.x - asteroid.sprite.x).abs();
+    let dy = (asteroid2.sprite.y - asteroid.sprite.y).abs();
+    dx < (label_width(asteroid) + label_width(asteroid2)) * 0.5
+        && dy < (asteroid.sprite.height + asteroid2.sprite.height) * 0.5
+}
+
+//Returns true if the asteroid or its question would overlap another asteroid
+//at any point in the next `PATH_CHECK_TIME` seconds, `speed` is the speed
+//asteroids fall at on the current level
+fn intersects_another_asteroid(asteroid: &Asteroid, other: &[Asteroid], speed: f32) -> bool {
+    let steps = (PATH_CHECK_TIME / PATH_CHECK_STEP) as u32;
+    for asteroid2 in other {
+        if asteroid2.deleted {
+            continue;
+        }
+
+        let mut asteroid = asteroid.clone();
+        let mut asteroid2 = asteroid2.clone();
+        for _ in 0..=steps {
+            if overlaps(&asteroid, &asteroid2) {
+                return true;
+            }
+            asteroid.update(PATH_CHECK_STEP, speed);
+            asteroid2.update(PATH_CHECK_STEP, speed);
+        }
+    }
+
+    false
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameEvent {
+    //The player destroyed an asteroid by answering its card
+    AsteroidDestroyed { x: f32, y: f32 },
+    //An asteroid reached the planet
+    PlanetHit { x: f32, y: f32 },
+    //The player lost health by entering a wrong answer while a red asteroid
+    //was on the screen
+    WrongAnswer,
+    //The player advanced to a new level
+    LevelUp(u32),
+    //The player answered one of the cards on a boss
+    BossHit { x: f32, y: f32 },
+    //The shield absorbed an asteroid that reached the planet
+    ShieldBlocked { x: f32, y: f32 },
+    PowerUpEarned(PowerUp),
+    PowerUpUsed(PowerUp),
+}
+
+pub struct Simulation {
+    rng: StdRng,
+    //Picks the cards on new asteroids
+    pub selector: CardSelector,
+    //Takes effect when the game restarts
+    pub difficulty: Difficulty,
+    asteroid_spawn_timer: f32,
+    spawn_interval: f32,
+    pub asteroids: Vec<Asteroid>,
+    pub health: u32,
+    pub score: u64,
+    pub level: u32,
+    //Number of correct answers in a row, a wrong answer or an asteroid
+    //hitting the planet resets it
+    pub streak: u32,
+    pub best_streak: u32,
+    //Number of each power-up the player has, in the order of `PowerUp::ALL`
+    powerups: [u32; PowerUp::ALL.len()],
+    pub shield_active: bool,
+    //Time left until asteroids fall at normal speed again
+    pub slow_timer: f32,
+    //Every time the player destroys an asteroid, this decreases by 1
+    //When this hits 0, advance to the next level
+    asteroids_until_next_level: u32,
+    //Set once the boss of the current level has spawned
+    boss_spawned: bool,
+    pub log: VecDeque<LogItem>,
+    //Number of answers submitted and how many of them destroyed an asteroid
+    answers_submitted: u32,
+    answers_correct: u32,
+    //Events since the last call to `take_events`
+    events: Vec<GameEvent>,
+}
+
+impl Simulation {
+    pub fn new(seed: u64) -> Self {
+        let difficulty = Difficulty::new();
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            selector: CardSelector::new(&[]),
+            asteroid_spawn_timer: 0.0,
+            spawn_interval: difficulty.spawn_interval(1),
+            asteroids: vec![],
+            health: difficulty.health,
+            score: 0,
+            level: 1,
+            streak: 0,
+            best_streak: 0,
+            powerups: [0; PowerUp::ALL.len()],
+            shield_active: false,
+            slow_timer: 0.0,
+            asteroids_until_next_level: difficulty.asteroids_until_next(1),
+            boss_spawned: false,
+            log: VecDeque::new(),
+            answers_submitted: 0,
+            answers_correct: 0,
+            events: vec![],
+            difficulty,
+        }
+    }
+
+    //Restarts the rng so that the rest of the session can be reproduced
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+
+    pub fn set_cards(&mut self, cards: &[Flashcard]) {
+        let mode = self.selector.mode;
+        self.selector = CardSelector::new(cards);
+        self.selector.mode = mode;
+    }
+
+    pub fn restart(&mut self) {
+        self.asteroid_spawn_timer = 0.0;
+        self.asteroids.clear();
+        self.health = self.difficulty.health;
+        self.score = 0;
+        self.level = 1;
+        self.streak = 0;
+        self.best_streak = 0;
+        self.powerups = [0; PowerUp::ALL.len()];
+        self.shield_active = false;
+        self.slow_timer = 0.0;
+        self.spawn_interval = self.difficulty.spawn_interval(self.level);
+        self.asteroids_until_next_level = self.difficulty.asteroids_until_next(self.level);
+        self.boss_spawned = false;
+        self.log.clear();
+        self.selector.reset();
+        self.answers_submitted = 0;
+        self.answers_correct = 0;
+        self.events.clear();
+    }
+
+    //Returns the events that happened since this was last called
+    pub fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub fn get_random_card(&mut self) -> Flashcard {
+        self.selector.pick(&mut self.rng)
+    }
+
+    pub fn spawn_asteroid(&mut self, dt: f32) {
+        //Do not spawn any extra asteroids if we are advancing to the next level
+        if self.asteroids_until_next_level == 0 {
+            return;
+        }
+
+        self.asteroid_spawn_timer -= dt;
+        if self.asteroid_spawn_timer > 0.0 {
+            return;
+        }
+        if self.asteroids_until_next_level == 0 {
+            return;
+        }
+        self.asteroid_spawn_timer = self.spawn_interval;
+
+        let range = CANVAS_W - ASTEROID_SIZE * 2.0;
+        //Spawn an extra asteroid
+        if self.rng.random() {
+            let x = self.rng.random::<f32>() * range + ASTEROID_SIZE - CANVAS_W / 2.0;
+            let y = CANVAS_H + ASTEROID_SIZE + self.rng.random::<f32>() * 320.0;
+            self.try_spawn_asteroid(x, y);
+        }
+
+        //In later levels spawn a third asteroid
+        if self.rng.random::<u32>() % 4 == 0 && self.level >= self.difficulty.third_asteroid_level {
+            let x = self.rng.random::<f32>() * range + ASTEROID_SIZE - CANVAS_W / 2.0;
+            let y = CANVAS_H + ASTEROID_SIZE + self.rng.random::<f32>() * 320.0;
+            self.try_spawn_asteroid(x, y);
+        }
+
+        let x = self.rng.random::<f32>() * range + ASTEROID_SIZE - CANVAS_W / 2.0;
+        let y = CANVAS_H / 2.0 + ASTEROID_SIZE / 2.0;
+        self.try_spawn_asteroid(x, y);
+    }
+
+    //Spawns an asteroid at (x, y) unless it would overlap another asteroid
+    fn try_spawn_asteroid(&mut self, x: f32, y: f32) {
+        let rotation = self.rng.random::<f32>() * std::f32::consts::PI * 2.0;
+        let flashcard = self.get_random_card();
+        let red = self.difficulty.is_red(self.rng.random(), self.level);
+        let fragments = if red {
+            vec![]
+        } else {
+            self.pick_fragments(&flashcard)
+        };
+        let size = if fragments.is_empty() {
+            ASTEROID_SIZE
+        } else {
+            SPLITTER_SIZE
+        };
+        let speed_scale = self.difficulty.answer_speed(&flashcard.answer);
+        let mut new_asteroid = Asteroid::new(x, y, size, rotation, flashcard, red);
+        new_asteroid.fragments = fragments;
+        new_asteroid.speed_scale = speed_scale;
+        let movement = self.random_movement();
+        let direction = if self.rng.random() { 1.0 } else { -1.0 };
+        new_asteroid.set_movement(movement, direction);
+        //Red asteroids are dangerous enough without tempting the player
+        if !red && self.rng.random_range(0..POWERUP_ASTEROID_CHANCE) == 0 {
+            new_asteroid.powerup = Some(self.random_powerup());
+        }
+        let speed = self.difficulty.asteroid_speed(self.level);
+        new_asteroid.predict_fall(speed);
+        if !intersects_another_asteroid(&new_asteroid, &self.asteroids, speed) {
+            self.asteroids.push(new_asteroid);
+        }
+    }
+
+    //Picks how a new asteroid moves from the movements unlocked on the
+    //current level
+    fn random_movement(&mut self) -> Movement {
+        let movements = self.difficulty.movements(self.level);
+        let chance = self.difficulty.movement_chance;
+        if movements.is_empty() || chance == 0 || self.rng.random_range(0..chance) != 0 {
+            return Movement::Straight;
+        }
+        movements[self.rng.random_range(0..movements.len())]
+    }
+
+    //Returns the cards for the fragments of a new asteroid, this is empty
+    //if the asteroid should not split
+    fn pick_fragments(&mut self, card: &Flashcard) -> Vec<Flashcard> {
+        if self.rng.random_range(0..SPLIT_CHANCE) != 0 {
+            return vec![];
+        }
+        let mut related = self.selector.related(card);
+        if related.len() < MIN_FRAGMENTS {
+            return vec![];
+        }
+        related.shuffle(&mut self.rng);
+        let count = self.rng.random_range(MIN_FRAGMENTS..=MAX_FRAGMENTS);
+        related.truncate(count);
+        related
+    }
+
+    //Spawns the fragments of a destroyed asteroid at its position, spread
+    //out from left to right
+    fn split(&mut self, index: usize) {
+        let parent = &self.asteroids[index];
+        let (x, y) = (parent.sprite.x, parent.sprite.y);
+        let cards = parent.fragments.clone();
+        let middle = (cards.len() as f32 - 1.0) / 2.0;
+        let speed = self.difficulty.asteroid_speed(self.level);
+        for (i, card) in cards.into_iter().enumerate() {
+            let offset = i as f32 - middle;
+            let vx = FRAGMENT_SPREAD * offset;
+            //Start the fragments slightly apart so their questions do not overlap
+            let x = x + offset * FRAGMENT_SIZE / 2.0;
+            let mut fragment =
+                Asteroid::new_fragment(x, y, FRAGMENT_SIZE, card, vx, FRAGMENT_SPEED);
+            fragment.predict_fall(speed);
+            self.asteroids.push(fragment);
+        }
+    }
+
+    fn spawn_boss(&mut self) {
+        let card_count = self.rng.random_range(BOSS_MIN_CARDS..=BOSS_MAX_CARDS);
+        let cards: Vec<Flashcard> = (0..card_count).map(|_| self.get_random_card()).collect();
+        let range = CANVAS_W - BOSS_SIZE * 2.0;
+        let x = self.rng.random::<f32>() * range + BOSS_SIZE - CANVAS_W / 2.0;
+        let y = CANVAS_H / 2.0 + BOSS_SIZE / 2.0;
+        let rotation = self.rng.random::<f32>() * std::f32::consts::PI * 2.0;
+        let mut boss = Asteroid::new_boss(x, y, BOSS_SIZE, rotation, &cards, BOSS_SPEED);
+        boss.predict_fall(self.difficulty.asteroid_speed(self.level));
+        self.asteroids.push(boss);
+        self.boss_spawned = true;
+    }
+
+    fn random_powerup(&mut self) -> PowerUp {
+        PowerUp::ALL[self.rng.random_range(0..PowerUp::ALL.len())]
+    }
+
+    pub fn powerup_count(&self, powerup: PowerUp) -> u32 {
+        self.powerups[powerup.index()]
+    }
+
+    fn earn_powerup(&mut self, powerup: PowerUp) {
+        let count = &mut self.powerups[powerup.index()];
+        if *count < MAX_POWERUPS {
+            *count += 1;
+            self.events.push(GameEvent::PowerUpEarned(powerup));
+        }
+    }
+
+    //Uses a power-up if the player has one, returns false if it could not be
+    //used
+    pub fn use_powerup(&mut self, powerup: PowerUp) -> bool {
+        if self.game_over() || self.powerup_count(powerup) == 0 {
+            return false;
+        }
+
+        match powerup {
+            PowerUp::Shield => {
+                if self.shield_active {
+                    return false;
+                }
+                self.shield_active = true;
+            }
+            PowerUp::SlowTime => self.slow_timer = SLOW_TIME_LENGTH,
+            PowerUp::Bomb => self.bomb(),
+            PowerUp::ExtraHeart => self.health += 1,
+        }
+        self.powerups[powerup.index()] -= 1;
+        self.events.push(GameEvent::PowerUpUsed(powerup));
+        true
+    }
+
+    //Destroys every asteroid on the screen, this does not give any points
+    //but the asteroids count towards the next level, bosses only lose one
+    //hit point
+    fn bomb(&mut self) {
+        let mut keep = vec![];
+        for asteroid in &self.asteroids {
+            if asteroid.deleted || asteroid.above_top() {
+                keep.push(asteroid.clone());
+                continue;
+            }
+            let (x, y) = (asteroid.sprite.x, asteroid.sprite.y);
+            if asteroid.is_boss() {
+                let mut boss = asteroid.clone();
+                if !boss.hit() {
+                    self.events.push(GameEvent::BossHit { x, y });
+                    keep.push(boss);
+                    continue;
+                }
+            }
+            self.events.push(GameEvent::AsteroidDestroyed { x, y });
+            if asteroid.counts_for_level && self.asteroids_until_next_level > 0 {
+                self.asteroids_until_next_level -= 1;
+            }
+        }
+        self.asteroids = keep;
+    }
+
+    //Returns the fraction of submitted answers that destroyed an asteroid
+    pub fn accuracy(&self) -> f32 {
+        if self.answers_submitted == 0 {
+            return 0.0;
+        }
+        self.answers_correct as f32 / self.answers_submitted as f32
+    }
+
+    //The score of each asteroid is multiplied by this
+    pub fn multiplier(&self) -> u32 {
+        (1 + self.streak / STREAK_PER_MULTIPLIER).min(MAX_MULTIPLIER)
+    }
+
+    //Returns if its game over
+    pub fn game_over(&self) -> bool {
+        self.health == 0
+    }
+
+    //Update log
+    pub fn update_log(&mut self, dt: f32) {
+        if self.log.is_empty() {
+            return;
+        }
+
+        let can_pop = if let Some(log_item) = self.log.get_mut(0) {
+            log_item.update(dt);
+            log_item.can_delete() || self.log.len() > MAX_LOG_LEN
+        } else {
+            self.log.len() > MAX_LOG_LEN
+        };
+
+        if can_pop {
+            self.log.pop_front();
+            while self.log.len() > MAX_LOG_LEN {
+                self.log.pop_front();
+            }
+            if let Some(log_item) = self.log.get_mut(0) {
+                log_item.reset_timer();
+            }
+        }
+    }
+
+    pub fn delete_asteroids(&mut self) {
+        //Delete asteroids
+        let mut keep = vec![];
+        for asteroid in &self.asteroids {
+            if !asteroid.deleted {
+                keep.push(asteroid.clone());
+                continue;
+            }
+
+            let (x, y) = (asteroid.sprite.x, asteroid.sprite.y);
+            //The shield absorbs the hit, even from a red asteroid
+            if asteroid.at_bottom() && self.shield_active {
+                self.shield_active = false;
+                self.streak = 0;
+                self.log.push_back(LogItem::new(asteroid.flashcard.clone()));
+                self.selector.record_miss(&asteroid.flashcard);
+                self.events.push(GameEvent::ShieldBlocked { x, y });
+                continue;
+            }
+
+            //If the asteroid hits the bottom of the screen, lose health
+            if asteroid.at_bottom() && self.health > 0 {
+                self.log.push_back(LogItem::new(asteroid.flashcard.clone()));
+                self.health -= 1;
+                //If it's red, then lose instantly
+                if asteroid.is_red {
+                    self.health = 0;
+                }
+            }
+
+            if asteroid.at_bottom() {
+                self.streak = 0;
+                self.selector.record_miss(&asteroid.flashcard);
+                self.events.push(GameEvent::PlanetHit { x, y });
+            } else if asteroid.destroyed {
+                self.selector
+                    .record_answer(&asteroid.flashcard, asteroid.age);
+                self.events.push(GameEvent::AsteroidDestroyed { x, y });
+            }
+        }
+        self.asteroids = keep;
+    }
+
+    //Destroys the lowest asteroid with `answer` as its answer, if there is no
+    //such asteroid and there is a red asteroid on the screen, lose health
+    pub fn submit_answer(&mut self, answer: &str) {
+        //Ignore if game over
+        if self.game_over() || answer.is_empty() {
+            return;
+        }
+
+        //Destroy asteroids
+        //Find the lowest asteroid
+        let mut index = None;
+        let mut lowest_y = 999.0;
+        let mut found_red = false;
+        for (i, asteroid) in self.asteroids.iter().enumerate() {
+            //ignore asteroids that are off-screen
+            if asteroid.above_top() {
+                continue;
+            }
+
+            if asteroid.is_red {
+                found_red = true;
+            }
+
+            if asteroid.flashcard.answer == answer && lowest_y > asteroid.sprite.y {
+                lowest_y = asteroid.sprite.y;
+                index = Some(i);
+            }
+        }
+
+        self.answers_submitted += 1;
+        if let Some(index) = index {
+            self.answers_correct += 1;
+            let counts_for_level = self.asteroids[index].counts_for_level;
+            if self.asteroids[index].hit() {
+                self.asteroids[index].deleted = true;
+                self.asteroids[index].destroyed = true;
+                self.split(index);
+            } else {
+                let (x, y) = (
+                    self.asteroids[index].sprite.x,
+                    self.asteroids[index].sprite.y,
+                );
+                self.events.push(GameEvent::BossHit { x, y });
+            }
+            let asteroid = &self.asteroids[index];
+            let carried_powerup = asteroid.powerup;
+            let points = self.difficulty.points(asteroid.is_red, self.level);
+            let time_left = asteroid.fall_time_left();
+            self.streak += 1;
+            self.best_streak = self.best_streak.max(self.streak);
+            self.score += (points + speed_bonus(points, time_left)) * self.multiplier() as u64;
+            if let Some(powerup) = carried_powerup {
+                self.earn_powerup(powerup);
+            }
+            if self.streak.is_multiple_of(POWERUP_STREAK) {
+                let powerup = self.random_powerup();
+                self.earn_powerup(powerup);
+            }
+            if counts_for_level && self.asteroids_until_next_level > 0 {
+                self.asteroids_until_next_level -= 1;
+            }
+        } else {
+            self.streak = 0;
+            for asteroid in &mut self.asteroids {
+                if asteroid.is_boss() && !asteroid.above_top() {
+                    asteroid.speed_scale =
+                        (asteroid.speed_scale + BOSS_SPEEDUP).min(BOSS_MAX_SPEED);
+                }
+            }
+        }
+
+        //lose helath if we enter something wrong and there is a red asteroid
+        //on the screen and destroy all red asteroids on the screen
+        if found_red && index.is_none() && self.health > 0 {
+            self.health -= 1;
+            self.events.push(GameEvent::WrongAnswer);
+        }
+    }
+
+    pub fn advance_to_next_level(&mut self) {
+        if self.game_over() {
+            return;
+        }
+
+        //If we are to advance ont the next level, delete any asteroids that
+        //are above the top of the screen
+        if self.asteroids_until_next_level == 0 {
+            self.asteroids = self
+                .asteroids
+                .iter()
+                .filter(|asteroid| !asteroid.above_top())
+                .cloned()
+                .collect();
+        }
+
+        //Count any non-destroyed asteroids
+        let mut count = 0;
+        for asteroid in &self.asteroids {
+            if asteroid.destroyed || asteroid.deleted {
+                continue;
+            }
+            count += 1;
+        }
+
+        //Once the asteroids of the level are gone the boss appears, after the
+        //boss is gone advance to the next level
+        if self.asteroids_until_next_level == 0 && count == 0 && !self.boss_spawned {
+            self.spawn_boss();
+        } else if self.asteroids_until_next_level == 0 && count == 0 {
+            self.level += 1;
+            self.boss_spawned = false;
+            self.asteroids_until_next_level = self.difficulty.asteroids_until_next(self.level);
+            self.spawn_interval = self.difficulty.spawn_interval(self.level);
+            self.events.push(GameEvent::LevelUp(self.level));
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        //Delete asteroids
+        self.delete_asteroids();
+
+        //Stop updating if game over
+        if self.game_over() {
+            return;
+        }
+
+        //Spawn asteroids
+        self.spawn_asteroid(dt);
+
+        //Update asteroids
+        let mut speed = self.difficulty.asteroid_speed(self.level);
+        if self.slow_timer > 0.0 {
+            self.slow_timer -= dt;
+            speed *= SLOW_TIME_SPEED;
+        }
+        for asteroid in &mut self.asteroids {
+            asteroid.update(dt, speed);
+        }
+
+        self.advance_to_next_level();
+        self.update_log(dt);
+    }
+}
@@ -0,0 +1,312 @@
+#[cfg(test)]
+use super::*;
+
+#[cfg(test)]
+fn test_cards() -> Vec<Flashcard> {
+    vec![
+        Flashcard::new("1 + 1", "2", "math"),
+        Flashcard::new("2 + 2", "4", "math"),
+        Flashcard::new("3 + 3", "6", "math"),
+    ]
+}
+
+#[cfg(test)]
+fn test_sim(seed: u64) -> Simulation {
+    let mut sim = Simulation::new(seed);
+    sim.set_cards(&test_cards());
+    sim
+}
+
+#[test]
+fn test_same_seed_same_asteroids() {
+    let mut sim1 = test_sim(42);
+    let mut sim2 = test_sim(42);
+    for _ in 0..600 {
+        sim1.update(1.0 / 60.0);
+        sim2.update(1.0 / 60.0);
+    }
+    assert!(!sim1.asteroids.is_empty());
+    assert_eq!(sim1.asteroids.len(), sim2.asteroids.len());
+    for (a, b) in sim1.asteroids.iter().zip(&sim2.asteroids) {
+        assert_eq!(a.sprite.x, b.sprite.x);
+        assert_eq!(a.sprite.y, b.sprite.y);
+        assert!(a.flashcard == b.flashcard);
+    }
+}
+
+#[test]
+fn test_destroy_asteroid() {
+    let mut sim = test_sim(0);
+    let card = Flashcard::new("1 + 1", "2", "math");
+    sim.asteroids
+        .push(Asteroid::new(0.0, 0.0, ASTEROID_SIZE, 0.0, card, false));
+    sim.submit_answer("2");
+    assert_eq!(sim.score, 100);
+    sim.delete_asteroids();
+    assert!(sim.asteroids.is_empty());
+    let events = sim.take_events();
+    assert_eq!(
+        events,
+        vec![GameEvent::AsteroidDestroyed { x: 0.0, y: 0.0 }]
+    );
+    assert!(sim.take_events().is_empty());
+}
+
+#[test]
+fn test_wrong_answer_with_red_asteroid() {
+    let mut sim = test_sim(0);
+    let card = Flashcard::new("1 + 1", "2", "math");
+    sim.asteroids
+        .push(Asteroid::new(0.0, 0.0, ASTEROID_SIZE, 0.0, card, true));
+    sim.submit_answer("3");
+    assert_eq!(sim.health, Difficulty::new().health - 1);
+    assert_eq!(sim.take_events(), vec![GameEvent::WrongAnswer]);
+}
+
+#[test]
+fn test_planet_hit() {
+    let mut sim = test_sim(0);
+    let card = Flashcard::new("1 + 1", "2", "math");
+    let y = -CANVAS_H / 2.0 + 1.0;
+    sim.asteroids
+        .push(Asteroid::new(0.0, y, ASTEROID_SIZE, 0.0, card, false));
+    sim.update(1.0);
+    sim.update(0.0);
+    assert_eq!(sim.health, Difficulty::new().health - 1);
+    assert_eq!(sim.log.len(), 1);
+    let events = sim.take_events();
+    assert!(matches!(events[0], GameEvent::PlanetHit { .. }));
+}
+
+#[test]
+fn test_level_up() {
+    let mut sim = test_sim(0);
+    sim.asteroids_until_next_level = 1;
+    let card = Flashcard::new("1 + 1", "2", "math");
+    sim.asteroids
+        .push(Asteroid::new(0.0, 0.0, ASTEROID_SIZE, 0.0, card, false));
+    sim.submit_answer("2");
+    sim.update(0.0);
+    //The boss appears before the next level
+    assert_eq!(sim.level, 1);
+    assert_eq!(sim.asteroids.len(), 1);
+    assert!(sim.asteroids[0].is_boss());
+    while let Some(boss) = sim.asteroids.first() {
+        let answer = boss.flashcard.answer.clone();
+        sim.submit_answer(&answer);
+        sim.update(0.0);
+    }
+    sim.update(0.0);
+    assert_eq!(sim.level, 2);
+    assert!(sim.take_events().contains(&GameEvent::LevelUp(2)));
+}
+
+#[test]
+fn test_boss_needs_every_card() {
+    let mut sim = test_sim(0);
+    let cards = test_cards();
+    sim.asteroids.push(Asteroid::new_boss(
+        0.0, 0.0, BOSS_SIZE, 0.0, &cards, BOSS_SPEED,
+    ));
+    for card in &cards[..cards.len() - 1] {
+        sim.submit_answer(&card.answer);
+        assert!(!sim.asteroids[0].deleted);
+    }
+    assert_eq!(sim.asteroids[0].hit_points, 1);
+    sim.submit_answer(&cards[cards.len() - 1].answer);
+    assert!(sim.asteroids[0].destroyed);
+}
+
+#[test]
+fn test_wrong_answer_speeds_up_boss() {
+    let mut sim = test_sim(0);
+    sim.asteroids.push(Asteroid::new_boss(
+        0.0,
+        0.0,
+        BOSS_SIZE,
+        0.0,
+        &test_cards(),
+        BOSS_SPEED,
+    ));
+    sim.submit_answer("wrong");
+    assert!(sim.asteroids[0].speed_scale > BOSS_SPEED);
+    assert_eq!(sim.streak, 0);
+}
+
+#[test]
+fn test_streak_multiplier() {
+    let mut sim = test_sim(0);
+    for _ in 0..STREAK_PER_MULTIPLIER {
+        let card = Flashcard::new("1 + 1", "2", "math");
+        sim.asteroids
+            .push(Asteroid::new(0.0, 0.0, ASTEROID_SIZE, 0.0, card, false));
+        sim.submit_answer("2");
+        sim.delete_asteroids();
+    }
+    assert_eq!(sim.streak, STREAK_PER_MULTIPLIER);
+    assert_eq!(sim.multiplier(), 2);
+    //The last asteroid was worth twice as much
+    assert_eq!(sim.score, 100 * (STREAK_PER_MULTIPLIER as u64 - 1) + 200);
+    sim.submit_answer("3");
+    assert_eq!(sim.streak, 0);
+    assert_eq!(sim.multiplier(), 1);
+    assert_eq!(sim.best_streak, STREAK_PER_MULTIPLIER);
+}
+
+#[test]
+fn test_speed_bonus() {
+    let card = Flashcard::new("1 + 1", "2", "math");
+    let y = CANVAS_H / 2.0;
+    let mut sim = test_sim(0);
+    let speed = sim.difficulty.asteroid_speed(1);
+    let mut asteroid = Asteroid::new(0.0, y, ASTEROID_SIZE, 0.0, card, false);
+    asteroid.predict_fall(speed);
+
+    //Answered as soon as it came onto the screen
+    sim.asteroids.push(asteroid.clone());
+    sim.submit_answer("2");
+    assert_eq!(sim.score, 150);
+
+    //Comets start slow, so a comet near the top has used up most of its fall time
+    let mut sim = test_sim(0);
+    let mut comet = asteroid.clone();
+    comet.set_movement(Movement::Comet, 1.0);
+    comet.predict_fall(speed);
+    while comet.sprite.y > CANVAS_H / 4.0 {
+        comet.update(1.0 / 60.0, speed);
+    }
+    assert!(comet.fall_time_left() < 0.75);
+    sim.asteroids.push(comet);
+    sim.submit_answer("2");
+    assert!(sim.score < 125);
+
+    //Not predicted, no bonus
+    let mut sim = test_sim(0);
+    let card = Flashcard::new("1 + 1", "2", "math");
+    sim.asteroids
+        .push(Asteroid::new(0.0, y, ASTEROID_SIZE, 0.0, card, false));
+    sim.submit_answer("2");
+    assert_eq!(sim.score, 100);
+}
+
+#[test]
+fn test_streak_earns_powerup() {
+    let mut sim = test_sim(0);
+    for _ in 0..POWERUP_STREAK {
+        let card = Flashcard::new("1 + 1", "2", "math");
+        sim.asteroids
+            .push(Asteroid::new(0.0, 0.0, ASTEROID_SIZE, 0.0, card, false));
+        sim.submit_answer("2");
+        sim.delete_asteroids();
+    }
+    let total: u32 = PowerUp::ALL.iter().map(|p| sim.powerup_count(*p)).sum();
+    assert_eq!(total, 1);
+}
+
+#[test]
+fn test_shield_absorbs_hit() {
+    let mut sim = test_sim(0);
+    sim.powerups[PowerUp::Shield.index()] = 1;
+    assert!(sim.use_powerup(PowerUp::Shield));
+    assert!(!sim.use_powerup(PowerUp::Shield));
+    let card = Flashcard::new("1 + 1", "2", "math");
+    let y = -CANVAS_H / 2.0 + 1.0;
+    sim.asteroids
+        .push(Asteroid::new(0.0, y, ASTEROID_SIZE, 0.0, card, true));
+    sim.update(1.0);
+    sim.update(0.0);
+    assert_eq!(sim.health, Difficulty::new().health);
+    assert!(!sim.shield_active);
+    let events = sim.take_events();
+    assert!(
+        events
+            .iter()
+            .any(|event| matches!(event, GameEvent::ShieldBlocked { .. }))
+    );
+}
+
+#[test]
+fn test_bomb_clears_screen() {
+    let mut sim = test_sim(0);
+    sim.powerups[PowerUp::Bomb.index()] = 1;
+    let card = Flashcard::new("1 + 1", "2", "math");
+    sim.asteroids.push(Asteroid::new(
+        0.0,
+        0.0,
+        ASTEROID_SIZE,
+        0.0,
+        card.clone(),
+        false,
+    ));
+    //Above the top of the screen
+    sim.asteroids.push(Asteroid::new(
+        0.0,
+        CANVAS_H,
+        ASTEROID_SIZE,
+        0.0,
+        card.clone(),
+        false,
+    ));
+    //Fragments do not count towards the next level
+    sim.asteroids.push(Asteroid::new_fragment(
+        0.0,
+        0.0,
+        FRAGMENT_SIZE,
+        card,
+        0.0,
+        1.0,
+    ));
+    let until_next = sim.asteroids_until_next_level;
+    assert!(sim.use_powerup(PowerUp::Bomb));
+    assert_eq!(sim.asteroids.len(), 1);
+    assert_eq!(sim.asteroids_until_next_level, until_next - 1);
+    assert_eq!(sim.powerup_count(PowerUp::Bomb), 0);
+    assert!(!sim.use_powerup(PowerUp::Bomb));
+}
+
+#[test]
+fn test_asteroid_splits() {
+    let mut sim = test_sim(0);
+    let card = Flashcard::new("1 + 1", "2", "math");
+    let mut asteroid = Asteroid::new(0.0, 0.0, SPLITTER_SIZE, 0.0, card.clone(), false);
+    asteroid.fragments = sim.selector.related(&card);
+    assert_eq!(asteroid.fragments.len(), 2);
+    sim.asteroids.push(asteroid);
+    let until_next = sim.asteroids_until_next_level;
+    sim.submit_answer("2");
+    sim.delete_asteroids();
+    assert_eq!(sim.asteroids.len(), 2);
+    assert!(sim.asteroids[0].sprite.vx < 0.0);
+    assert!(sim.asteroids[1].sprite.vx > 0.0);
+    //Only the parent counts towards the next level
+    sim.submit_answer("4");
+    sim.submit_answer("6");
+    assert_eq!(sim.asteroids_until_next_level, until_next - 1);
+}
+
+#[test]
+fn test_zigzag_changes_direction() {
+    let card = Flashcard::new("1 + 1", "2", "math");
+    let mut asteroid = Asteroid::new(0.0, 0.0, ASTEROID_SIZE, 0.0, card, false);
+    asteroid.set_movement(Movement::ZigZag, 1.0);
+    let start_x = asteroid.sprite.x;
+    asteroid.update(1.0, 10.0);
+    assert!(asteroid.sprite.x > start_x);
+    let right_x = asteroid.sprite.x;
+    asteroid.update(1.0, 10.0);
+    assert!(asteroid.sprite.x < right_x);
+}
+
+#[test]
+fn test_crossing_paths_intersect() {
+    let card = Flashcard::new("1 + 1", "2", "math");
+    let falling = Asteroid::new(200.0, 0.0, ASTEROID_SIZE, 0.0, card.clone(), false);
+    let mut drifting = Asteroid::new(100.0, 0.0, ASTEROID_SIZE, 0.0, card.clone(), false);
+    let speed = CANVAS_H / 25.0;
+    //Both fall at the same speed so they never meet
+    let others = std::slice::from_ref(&falling);
+    assert!(!intersects_another_asteroid(&drifting, others, speed));
+    //Drifting to the right crosses the path of the other asteroid
+    drifting.set_movement(Movement::Diagonal, 1.0);
+    assert!(intersects_another_asteroid(&drifting, &[falling], speed));
+}
@@ -2,8 +2,18 @@
  * This file contains code for various sprites in the game
  * */
 
-use super::draw::CANVAS_H;
+use super::{
+    draw::{CANVAS_H, CANVAS_W},
+    movement::Movement,
+    powerup::PowerUp,
+};
 use crate::flashcards::Flashcard;
+use std::collections::VecDeque;
+
+//Step used to predict how long an asteroid takes to fall (in seconds)
+const FALL_PREDICT_STEP: f32 = 1.0 / 60.0;
+//Stop predicting if an asteroid has not reached the planet after this long
+const MAX_FALL_TIME: f32 = 120.0;
 
 #[derive(Clone)]
 pub struct Sprite2D {
@@ -11,6 +21,9 @@ pub struct Sprite2D {
     pub y: f32,
     pub width: f32,
     pub height: f32,
+    //Velocity, this is multiplied by the scale passed to `move_by`
+    pub vx: f32,
+    pub vy: f32,
 }
 
 impl Sprite2D {
@@ -20,8 +33,15 @@ impl Sprite2D {
             y: spritey,
             width: w,
             height: h,
+            vx: 0.0,
+            vy: 0.0,
         }
     }
+
+    pub fn move_by(&mut self, dt: f32, scale: f32) {
+        self.x += self.vx * scale * dt;
+        self.y += self.vy * scale * dt;
+    }
 }
 
 #[derive(Clone)]
@@ -33,21 +53,122 @@ pub struct Asteroid {
     //This is basically the same flag as `deleted` but if this is flagged then
     //it must spawn an explosion
     pub destroyed: bool,
+    //The card that is currently shown on the asteroid
     pub flashcard: Flashcard,
+    //Cards shown after the current card is answered (only bosses have these)
+    pub next_cards: VecDeque<Flashcard>,
+    //Number of correct answers needed to destroy the asteroid
+    pub hit_points: u32,
+    pub max_hit_points: u32,
+    //Multiplies the speed of the asteroid
+    pub speed_scale: f32,
+    //Set if destroying the asteroid advances the player towards the next
+    //level, bosses and fragments do not count
+    pub counts_for_level: bool,
+    //Cards of the fragments the asteroid splits into when it is destroyed
+    pub fragments: Vec<Flashcard>,
+    pub movement: Movement,
+    //1.0 if the asteroid moves to the right, -1.0 if it moves to the left
+    direction: f32,
     pub is_red: bool,
+    //How long the asteroid has existed (in seconds)
+    pub age: f32,
+    //Ages at which the asteroid is expected to come onto the screen and to
+    //reach the planet (in seconds), set when it spawns
+    enter_age: f32,
+    fall_age: f32,
+    //Power-up the player earns by destroying the asteroid
+    pub powerup: Option<PowerUp>,
 }
 
 impl Asteroid {
     //x, y, size, rotation, red
     pub fn new(x: f32, y: f32, sz: f32, r: f32, card: Flashcard, red: bool) -> Self {
+        let mut sprite = Sprite2D::new(x, y, sz, sz);
+        //Fall straight down
+        sprite.vy = -1.0;
         Self {
-            sprite: Sprite2D::new(x, y, sz, sz),
+            sprite,
             rotation: r,
             deleted: false,
             destroyed: false,
             flashcard: card,
+            next_cards: VecDeque::new(),
+            hit_points: 1,
+            max_hit_points: 1,
+            speed_scale: 1.0,
+            counts_for_level: true,
+            fragments: vec![],
+            movement: Movement::Straight,
+            direction: 1.0,
             is_red: red,
+            age: 0.0,
+            enter_age: 0.0,
+            fall_age: 0.0,
+            powerup: None,
+        }
+    }
+
+    //A boss asteroid shows its cards one at a time and is destroyed after the
+    //last one is answered
+    pub fn new_boss(x: f32, y: f32, sz: f32, r: f32, cards: &[Flashcard], speed: f32) -> Self {
+        let mut next_cards: VecDeque<Flashcard> = cards.iter().cloned().collect();
+        let card = next_cards.pop_front().unwrap_or(Flashcard::none());
+        let mut boss = Self::new(x, y, sz, r, card, false);
+        boss.hit_points = cards.len().max(1) as u32;
+        boss.max_hit_points = boss.hit_points;
+        boss.next_cards = next_cards;
+        boss.speed_scale = speed;
+        boss.counts_for_level = false;
+        boss
+    }
+
+    //`direction` is 1.0 to start moving right and -1.0 to start moving left
+    pub fn set_movement(&mut self, movement: Movement, direction: f32) {
+        self.movement = movement;
+        self.direction = direction;
+        self.sprite.width *= movement.size();
+        self.sprite.height *= movement.size();
+        self.sprite.vx = movement.sideways_speed(0.0) * direction;
+    }
+
+    pub fn is_boss(&self) -> bool {
+        self.max_hit_points > 1
+    }
+
+    //Removes a hit point and shows the next card, returns true if the
+    //asteroid has no hit points left
+    pub fn hit(&mut self) -> bool {
+        self.hit_points = self.hit_points.saturating_sub(1);
+        if let Some(card) = self.next_cards.pop_front() {
+            self.flashcard = card;
+        }
+        self.hit_points == 0
+    }
+
+    //Predicts when the asteroid comes onto the screen and reaches the planet
+    //if the level speed stays at `speed`
+    pub fn predict_fall(&mut self, speed: f32) {
+        let mut asteroid = self.clone();
+        let mut enter_age = None;
+        while !asteroid.at_bottom() && asteroid.age - self.age < MAX_FALL_TIME {
+            if enter_age.is_none() && !asteroid.above_top() {
+                enter_age = Some(asteroid.age);
+            }
+            asteroid.update(FALL_PREDICT_STEP, speed);
         }
+        self.enter_age = enter_age.unwrap_or(asteroid.age);
+        self.fall_age = asteroid.age;
+    }
+
+    //Returns the fraction (0.0 - 1.0) of the time the asteroid takes to fall
+    //across the screen that is left, this is 0.0 if the fall was not predicted
+    pub fn fall_time_left(&self) -> f32 {
+        let fall_time = self.fall_age - self.enter_age;
+        if fall_time <= 0.0 {
+            return 0.0;
+        }
+        (1.0 - (self.age - self.enter_age) / fall_time).clamp(0.0, 1.0)
     }
 
     //Returns true if the asteroid is at the bottom of the screen
@@ -60,8 +181,31 @@ impl Asteroid {
         self.sprite.y > CANVAS_H / 2.0 + self.sprite.height / 2.0
     }
 
+    //A small fast asteroid that flies out of a destroyed asteroid,
+    //`vx` is how fast it moves sideways relative to how fast it falls
+    pub fn new_fragment(x: f32, y: f32, sz: f32, card: Flashcard, vx: f32, speed: f32) -> Self {
+        let mut fragment = Self::new(x, y, sz, 0.0, card, false);
+        fragment.sprite.vx = vx;
+        fragment.speed_scale = speed;
+        fragment.counts_for_level = false;
+        fragment
+    }
+
+    //`speed` is the speed that asteroids fall at on the current level
     pub fn update(&mut self, dt: f32, speed: f32) {
-        self.sprite.y -= speed * dt;
+        self.age += dt;
+        if matches!(self.movement, Movement::Diagonal | Movement::ZigZag) {
+            self.sprite.vx = self.movement.sideways_speed(self.age) * self.direction;
+        }
+        let speed = speed * self.speed_scale * self.movement.speed(self.age);
+        self.sprite.move_by(dt, speed);
+        //Bounce off the sides of the screen
+        let max_x = CANVAS_W / 2.0 - self.sprite.width / 2.0;
+        if self.sprite.x.abs() > max_x {
+            self.sprite.x = self.sprite.x.clamp(-max_x, max_x);
+            self.sprite.vx = -self.sprite.vx;
+            self.direction = -self.direction;
+        }
         self.rotation += dt * std::f32::consts::PI / 4.0;
 
         if self.at_bottom() {
@@ -1,164 +1,42 @@
 use super::{
-    DAMAGE_ANIMATION_LENGTH, Game,
-    draw::{CANVAS_H, CANVAS_W},
-    sprite::{Asteroid, Explosion},
+    DAMAGE_ANIMATION_LENGTH, Game, LEVELUP_ANIMATION_LENGTH, simulation::GameEvent,
+    sprite::Explosion,
 };
-use crate::{flashcards::Flashcard, log::LogItem};
+use crate::{highscores::ScoreMode, replay::ReplayInput};
 
-const ASTEROID_SIZE: f32 = 80.0;
 pub const EXPLOSION_LIFETIME: f32 = 1.0; //1 second
-pub const MAX_LOG_LEN: usize = 16;
-
-fn calculate_asteroid_speed(level: u32) -> f32 {
-    CANVAS_H / (25.0 - (level - 1) as f32).max(5.0)
-}
-
-pub fn is_red(rand_value: u32, level: u32) -> bool {
-    match level {
-        1 => false,
-        2 => rand_value % 7 == 0,
-        3..=5 => rand_value % 10 == 0,
-        6..=8 => rand_value % 8 == 0,
-        9..=15 => rand_value % 6 == 0,
-        _ => rand_value % 5 == 0,
-    }
-}
-
-fn intersects_another_asteroid(asteroid: &Asteroid, other: &[Asteroid]) -> bool {
-    for asteroid2 in other {
-        let dx = asteroid2.sprite.x - asteroid.sprite.x;
-        let dy = asteroid2.sprite.y - asteroid.sprite.y;
-        let dist = (dx * dx + dy * dy).sqrt();
-        if dist < (asteroid.sprite.width + asteroid2.sprite.width) * 0.5 {
-            return true;
-        }
-    }
-
-    false
-}
 
 impl Game {
-    pub fn get_random_card(&self) -> Flashcard {
-        if self.flashcards.is_empty() {
-            Flashcard::none()
-        } else {
-            self.flashcards[rand::random_range(0..self.flashcards.len())].clone()
-        }
-    }
-
-    pub fn spawn_asteroid(&mut self, dt: f32) {
-        //Do not spawn any extra asteroids if we are advancing to the next level
-        if self.asteroids_until_next_level == 0 {
-            return;
-        }
-
-        self.asteroid_spawn_timer -= dt;
-        if self.asteroid_spawn_timer > 0.0 {
-            return;
-        }
-        if self.asteroids_until_next_level == 0 {
-            return;
-        }
-        self.asteroid_spawn_timer = self.spawn_interval;
-
-        let range = CANVAS_W - ASTEROID_SIZE * 2.0;
-        //Spawn an extra asteroid
-        if rand::random() {
-            let x = rand::random::<f32>() * range + ASTEROID_SIZE - CANVAS_W / 2.0;
-            let y = CANVAS_H + ASTEROID_SIZE + rand::random::<f32>() * 320.0;
-            let rotation = rand::random::<f32>() * std::f32::consts::PI * 2.0;
-            let flashcard = self.get_random_card();
-            let red = is_red(rand::random(), self.level);
-            let new_asteroid = Asteroid::new(x, y, ASTEROID_SIZE, rotation, flashcard, red);
-            if !intersects_another_asteroid(&new_asteroid, &self.asteroids) {
-                self.asteroids.push(new_asteroid);
-            }
-        }
-
-        //In later levels spawn a third asteroid
-        if rand::random::<u32>() % 4 == 0 && self.level >= 6 {
-            let x = rand::random::<f32>() * range + ASTEROID_SIZE - CANVAS_W / 2.0;
-            let y = CANVAS_H + ASTEROID_SIZE + rand::random::<f32>() * 320.0;
-            let rotation = rand::random::<f32>() * std::f32::consts::PI * 2.0;
-            let flashcard = self.get_random_card();
-            let red = is_red(rand::random(), self.level);
-            let new_asteroid = Asteroid::new(x, y, ASTEROID_SIZE, rotation, flashcard, red);
-            if !intersects_another_asteroid(&new_asteroid, &self.asteroids) {
-                self.asteroids.push(new_asteroid);
-            }
-        }
-
-        let x = rand::random::<f32>() * range + ASTEROID_SIZE - CANVAS_W / 2.0;
-        let y = CANVAS_H / 2.0 + ASTEROID_SIZE / 2.0;
-        let rotation = rand::random::<f32>() * std::f32::consts::PI * 2.0;
-        let flashcard = self.get_random_card();
-        let red = is_red(rand::random(), self.level);
-        let new_asteroid = Asteroid::new(x, y, ASTEROID_SIZE, rotation, flashcard, red);
-        if !intersects_another_asteroid(&new_asteroid, &self.asteroids) {
-            self.asteroids.push(new_asteroid);
-        }
-    }
-
-    //Returns if its game over
-    pub fn game_over(&self) -> bool {
-        self.health == 0
-    }
-
-    //Update log
-    pub fn update_log(&mut self, dt: f32) {
-        if self.log.is_empty() {
-            return;
-        }
-
-        let can_pop = if let Some(log_item) = self.log.get_mut(0) {
-            log_item.update(dt);
-            log_item.can_delete() || self.log.len() > MAX_LOG_LEN
-        } else {
-            self.log.len() > MAX_LOG_LEN
-        };
-
-        if can_pop {
-            self.log.pop_front();
-            while self.log.len() > MAX_LOG_LEN {
-                self.log.pop_front();
-            }
-            if let Some(log_item) = self.log.get_mut(0) {
-                log_item.reset_timer();
-            }
-        }
-    }
-
-    pub fn delete_asteroids(&mut self) {
-        //Delete asteroids
-        let mut keep = vec![];
-        for asteroid in &self.asteroids {
-            if !asteroid.deleted {
-                keep.push(asteroid.clone());
-                continue;
+    //Plays sounds and starts animations for the events from the simulation
+    fn handle_game_event(&mut self, event: GameEvent) {
+        match event {
+            GameEvent::AsteroidDestroyed { x, y } => {
+                self.explosions.push(Explosion::new(x, y));
+                self.audio.play("explosion");
             }
-
-            //If the asteroid hits the bottom of the screen, lose health
-            if asteroid.at_bottom() && self.health > 0 {
-                self.log.push_back(LogItem::new(asteroid.flashcard.clone()));
-                self.health -= 1;
-                //If it's red, then lose instantly
-                if asteroid.is_red {
-                    self.health = 0;
+            GameEvent::PlanetHit { x, y } => {
+                self.explosions.push(Explosion::new(x, y));
+                self.audio.play("explosion");
+                if !self.game_over() {
+                    self.damage_animation_timer = DAMAGE_ANIMATION_LENGTH;
                 }
-
-                if self.health > 0 {
+            }
+            GameEvent::WrongAnswer => {
+                if !self.game_over() {
                     self.damage_animation_timer = DAMAGE_ANIMATION_LENGTH;
                 }
             }
-
-            //Add explosion
-            if asteroid.at_bottom() || asteroid.destroyed {
-                let (x, y) = (asteroid.sprite.x, asteroid.sprite.y);
+            GameEvent::LevelUp(_) => {
+                self.levelup_animation_timer = LEVELUP_ANIMATION_LENGTH;
+                self.audio.play("levelup");
+            }
+            GameEvent::BossHit { x, y } | GameEvent::ShieldBlocked { x, y } => {
                 self.explosions.push(Explosion::new(x, y));
                 self.audio.play("explosion");
             }
+            GameEvent::PowerUpEarned(_) => self.audio.play("levelup"),
+            GameEvent::PowerUpUsed(_) => {}
         }
-        self.asteroids = keep;
     }
 
     pub fn update(&mut self, dt: f32) {
@@ -183,23 +61,28 @@ impl Game {
             .cloned()
             .collect();
 
-        //Delete asteroids
-        self.delete_asteroids();
-
-        //Stop updating if game over
-        if self.game_over() {
-            return;
+        //Submit the answers and use the power-ups from a replay on the step
+        //they were recorded on
+        if let Some(playback) = &mut self.playback {
+            for input in playback.inputs_at(self.step) {
+                match input {
+                    ReplayInput::Answer(answer) => self.sim.submit_answer(&answer),
+                    ReplayInput::PowerUp(powerup) => {
+                        self.sim.use_powerup(powerup);
+                    }
+                }
+            }
         }
 
-        //Spawn asteroids
-        self.spawn_asteroid(dt);
-
-        //Update asteroids
-        for asteroid in &mut self.asteroids {
-            asteroid.update(dt, calculate_asteroid_speed(self.level));
+        self.sim.update(dt);
+        self.step += 1;
+        for event in self.sim.take_events() {
+            self.handle_game_event(event);
         }
 
-        self.advance_to_next_level();
-        self.update_log(dt);
+        if self.game_over() {
+            self.save_replay();
+            self.record_high_score(ScoreMode::Asteroids);
+        }
     }
 }
@@ -1,14 +1,32 @@
+use crate::exam::{self, ExamConfig, ExamState, ReportFormat};
 use crate::flashcards::{self, Flashcard};
-use crate::game::draw::{CANVAS_H, CANVAS_W, caclulate_canv_offset, calculate_screen_scale};
+use crate::game::difficulty::{CUSTOM_DIFFICULTY, Difficulty};
+use crate::game::draw::{
+    CANVAS_H, CANVAS_W, POWERUP_ICON_SPACING, POWERUP_ICON_X, POWERUP_ICON_Y,
+    caclulate_canv_offset, calculate_screen_scale,
+};
+use crate::game::powerup::PowerUp;
+use crate::game::selection::CardSelection;
 use crate::game::{Game, GameScreen};
-use crate::learn::LearnState;
+use crate::highscores::{self, HighScores, ScoreMode};
+use crate::learn::{self, LearnConfig, LearnState, QuestionType, RequeueMode};
+use crate::matching::MatchState;
+use crate::progress::{self, Progress};
+use crate::settings::{self, Settings};
 use cgmath::Vector4;
-use egui_backend::egui::{self, ProgressBar, RichText};
+use egui_backend::egui::text::{CCursor, CCursorRange};
+use egui_backend::egui::text_edit::TextEditState;
+use egui_backend::egui::{self, Id, ProgressBar, RichText};
 use egui_backend::egui::{Align2, Color32, FontId, Pos2, RawInput, Rect, Ui, vec2};
 use egui_backend::{EguiInputState, Painter};
 use egui_gl_glfw as egui_backend;
 use glfw::{Window, WindowEvent};
 
+//Height of the special character palette bar
+const PALETTE_HEIGHT: f32 = 28.0;
+//Id of the text box used to type in answers
+const ANSWER_INPUT_ID: &str = "answer_input";
+
 //gui action
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum GuiAction {
@@ -19,6 +37,16 @@ pub enum GuiAction {
     Load,
     ToggleMute,
     GotoLearn,
+    StudyMissed,
+    StudyAll,
+    ResumeLearn,
+    RestartLearn,
+    LeaveLearn,
+    GotoMatch,
+    GotoExam,
+    SaveReport(ReportFormat),
+    GotoSettings,
+    SaveSettings,
     Quit,
 }
 
@@ -68,7 +96,7 @@ fn display_asteroid_text(gamestate: &Game, ui: &Ui) {
     let (w, h) = gamestate.get_window_size();
     let painter = ui.painter();
     let font_id = FontId::new(16.0, egui::FontFamily::Monospace);
-    for asteroid in &gamestate.asteroids {
+    for asteroid in &gamestate.sim.asteroids {
         let text_pos = world_to_eguipos(asteroid.sprite.x, asteroid.sprite.y, w, h);
         //Display the text
         painter.text(
@@ -78,6 +106,16 @@ fn display_asteroid_text(gamestate: &Game, ui: &Ui) {
             font_id.clone(),
             Color32::WHITE,
         );
+        //Display how many cards are left on a boss
+        if asteroid.is_boss() {
+            painter.text(
+                text_pos + vec2(0.0, 20.0),
+                Align2::CENTER_CENTER,
+                format!("{}/{}", asteroid.hit_points, asteroid.max_hit_points),
+                font_id.clone(),
+                Color32::YELLOW,
+            );
+        }
     }
 }
 
@@ -96,7 +134,7 @@ fn display_hud(gamestate: &Game, ui: &Ui) {
     painter.text(
         gui_pos(40.0, -16.0, w, h),
         Align2::LEFT_TOP,
-        format!("{}", gamestate.health),
+        format!("{}", gamestate.sim.health),
         font_id.clone(),
         Color32::WHITE,
     );
@@ -104,7 +142,7 @@ fn display_hud(gamestate: &Game, ui: &Ui) {
     painter.text(
         gui_pos(16.0, -40.0, w, h),
         Align2::LEFT_TOP,
-        format!("SCORE: {}", gamestate.score),
+        format!("SCORE: {}", gamestate.sim.score),
         font_id.clone(),
         Color32::WHITE,
     );
@@ -112,10 +150,52 @@ fn display_hud(gamestate: &Game, ui: &Ui) {
     painter.text(
         gui_pos(16.0, -64.0, w, h),
         Align2::LEFT_TOP,
-        format!("LEVEL: {}", gamestate.level),
+        format!("LEVEL: {}", gamestate.sim.level),
         font_id.clone(),
         Color32::WHITE,
     );
+    //Display combo
+    let combo_color = if gamestate.sim.multiplier() > 1 {
+        Color32::YELLOW
+    } else {
+        Color32::WHITE
+    };
+    painter.text(
+        gui_pos(16.0, -88.0, w, h),
+        Align2::LEFT_TOP,
+        format!(
+            "COMBO: {} (x{})",
+            gamestate.sim.streak,
+            gamestate.sim.multiplier()
+        ),
+        font_id.clone(),
+        combo_color,
+    );
+    //Display the number of each power-up and its hotkey
+    for (i, powerup) in PowerUp::ALL.iter().enumerate() {
+        let x = POWERUP_ICON_X + i as f32 * POWERUP_ICON_SPACING + 16.0;
+        let mut text = format!("{} F{}", gamestate.sim.powerup_count(*powerup), i + 1);
+        if *powerup == PowerUp::SlowTime && gamestate.sim.slow_timer > 0.0 {
+            text = format!("{:.1}s", gamestate.sim.slow_timer);
+        }
+        painter.text(
+            gui_pos(x, -(POWERUP_ICON_Y - 10.0), w, h),
+            Align2::LEFT_TOP,
+            text,
+            font_id.clone(),
+            Color32::WHITE,
+        );
+    }
+    //Show that a replay is being watched
+    if gamestate.playback.is_some() {
+        painter.text(
+            gui_pos(16.0, -112.0, w, h),
+            Align2::LEFT_TOP,
+            "REPLAY",
+            font_id.clone(),
+            Color32::YELLOW,
+        );
+    }
 }
 
 fn smoothstep_up(x: f32) -> f32 {
@@ -149,19 +229,29 @@ fn display_levelup(gamestate: &Game, ui: &Ui) {
     );
 }
 
+//Returns how much space the character palette takes up below the answer box
+fn palette_height(gamestate: &Game) -> f32 {
+    if gamestate.char_palette.is_empty() {
+        0.0
+    } else {
+        PALETTE_HEIGHT
+    }
+}
+
 fn display_log(gamestate: &Game, ui: &Ui, pixels_per_point: f32) {
-    if gamestate.log.is_empty() {
+    if gamestate.sim.log.is_empty() {
         return;
     }
 
     let (w, h) = gamestate.get_window_size();
+    let palette_h = palette_height(gamestate);
     let painter = ui.painter();
     let font_id = FontId::new(16.0, egui::FontFamily::Monospace);
-    for (i, log_item) in gamestate.log.iter().enumerate() {
+    for (i, log_item) in gamestate.sim.log.iter().enumerate() {
         //Calculate gui x position
         let gui_position = gui_pos(32.0, 0.0, w, h);
         //Calculate the y position (subtract size of window at bottom of screen)
-        let y = h as f32 / pixels_per_point - 56.0 - i as f32 * 24.0;
+        let y = h as f32 / pixels_per_point - 56.0 - palette_h - i as f32 * 24.0;
         painter.text(
             Pos2::new(gui_position.x, y),
             Align2::LEFT_BOTTOM,
@@ -191,6 +281,65 @@ fn update_action(action: Option<GuiAction>, new_action: Option<GuiAction>) -> Op
     }
 }
 
+//Text box for typing in an answer
+fn answer_input(ui: &mut Ui, answer: &mut String) {
+    ui.add(egui::TextEdit::singleline(answer).id(Id::new(ANSWER_INPUT_ID)));
+}
+
+//Inserts `ch` at the cursor of the answer text box (replacing any selected
+//text) and gives the text box focus again
+fn insert_at_cursor(ctx: &egui::Context, answer: &mut String, ch: char) {
+    let id = Id::new(ANSWER_INPUT_ID);
+    let mut state = TextEditState::load(ctx, id).unwrap_or_default();
+    let len = answer.chars().count();
+    let (start, end) = match state.cursor.char_range() {
+        Some(range) => {
+            let [min, max] = range.sorted();
+            (min.index.min(len), max.index.min(len))
+        }
+        None => (len, len),
+    };
+    //Convert character indices into byte indices
+    let byte_index = |index: usize| {
+        answer
+            .char_indices()
+            .nth(index)
+            .map(|(i, _)| i)
+            .unwrap_or(answer.len())
+    };
+    let (start_byte, end_byte) = (byte_index(start), byte_index(end));
+    answer.replace_range(start_byte..end_byte, &ch.to_string());
+    let cursor = CCursorRange::one(CCursor::new(start + 1));
+    state.cursor.set_char_range(Some(cursor));
+    state.store(ctx, id);
+    ctx.memory_mut(|mem| mem.request_focus(id));
+}
+
+//Displays a row of buttons for characters that are hard to type on most
+//keyboards, the first 10 characters can also be typed with Alt+number
+fn char_palette(ui: &mut Ui, answer: &mut String, palette: &[char]) {
+    if palette.is_empty() {
+        return;
+    }
+
+    ui.horizontal_wrapped(|ui| {
+        for (i, ch) in palette.iter().enumerate() {
+            let text = RichText::new(ch.to_string())
+                .size(14.0)
+                .color(Color32::WHITE);
+            let button = ui.button(text);
+            let button = if i < 10 {
+                button.on_hover_text(format!("Alt+{}", (i + 1) % 10))
+            } else {
+                button
+            };
+            if button.clicked() {
+                insert_at_cursor(ui.ctx(), answer, *ch);
+            }
+        }
+    });
+}
+
 fn display_lines(ui: &mut Ui, lines: &[String]) {
     for line in lines {
         let text = RichText::new(line).size(16.0).color(Color32::WHITE);
@@ -243,16 +392,21 @@ impl GuiController {
             });
 
         //Answer input box
+        let palette_h = palette_height(gamestate);
         egui::Window::new("bottom_panel")
             .movable(false)
             .title_bar(false)
             .scroll(true)
-            .fixed_size(vec2(w as f32 / pixels_per_point - 64.0, 64.0))
-            .fixed_pos(Pos2::new(24.0, h as f32 / pixels_per_point - 50.0))
+            .fixed_size(vec2(w as f32 / pixels_per_point - 64.0, 64.0 + palette_h))
+            .fixed_pos(Pos2::new(
+                24.0,
+                h as f32 / pixels_per_point - 50.0 - palette_h,
+            ))
             .show(&self.ctx, |ui| {
                 ui.vertical_centered(|ui| {
                     ui.label("Type your answer here (press enter to submit):");
-                    ui.text_edit_singleline(&mut gamestate.answer);
+                    answer_input(ui, &mut gamestate.answer);
+                    char_palette(ui, &mut gamestate.answer, &gamestate.char_palette);
                 })
             });
 
@@ -292,10 +446,20 @@ impl GuiController {
                     ui.vertical_centered(|ui| {
                         ui.add_space(height / 4.0);
                         ui.label(RichText::new("Game Over!").size(64.0).color(Color32::WHITE));
-                        let final_score = format!("Final Score: {}", gamestate.score);
+                        let final_score = format!("Final Score: {}", gamestate.sim.score);
                         ui.label(RichText::new(final_score).size(16.0).color(Color32::WHITE));
-                        let final_level = format!("Final Level: {}", gamestate.level);
+                        let final_level = format!("Final Level: {}", gamestate.sim.level);
                         ui.label(RichText::new(final_level).size(16.0).color(Color32::WHITE));
+                        let best_streak = format!("Best Streak: {}", gamestate.sim.best_streak);
+                        ui.label(RichText::new(best_streak).size(16.0).color(Color32::WHITE));
+                        ui.add_space(8.0);
+                        let highlight = gamestate.new_high_score;
+                        high_score_table(
+                            ui,
+                            &gamestate.high_scores,
+                            ScoreMode::Asteroids,
+                            highlight,
+                        );
                         ui.add_space(height / 32.0);
                         //Restart button
                         let restart = new_button(ui, "  Restart  ", 20.0, GuiAction::Restart);
@@ -365,6 +529,10 @@ impl GuiController {
                         new_button(ui, " Load Set ", 24.0, GuiAction::GotoLoadFlashcards);
                     action = update_action(action, load_set);
                     ui.add_space(height / 48.0);
+                    //Go to settings screen
+                    let settings = new_button(ui, " Settings ", 24.0, GuiAction::GotoSettings);
+                    action = update_action(action, settings);
+                    ui.add_space(height / 48.0);
                     //Go to about screen
                     let about = new_button(ui, "   About   ", 24.0, GuiAction::GotoAbout);
                     action = update_action(action, about);
@@ -481,6 +649,64 @@ impl GuiController {
         action
     }
 
+    //Display gui for settings menu
+    pub fn display_settings_screen(&mut self, gamestate: &mut Game) -> Option<GuiAction> {
+        let mut action = None;
+        let (w, h) = gamestate.get_window_size();
+
+        let pixels_per_point = self.input_state.pixels_per_point;
+        if self.ctx.pixels_per_point() != pixels_per_point {
+            self.ctx.set_pixels_per_point(pixels_per_point);
+        }
+        self.ctx.begin_pass(self.input_state.input.take());
+
+        let margin = 160.0;
+        let width = w as f32 / pixels_per_point - margin * 2.0;
+        let height = h as f32 / pixels_per_point - 32.0;
+        egui::Window::new("settings")
+            .frame(egui::Frame::new())
+            .movable(false)
+            .title_bar(false)
+            .scroll(true)
+            .hscroll(false)
+            .fixed_size(vec2(width, height))
+            .fixed_pos(Pos2::new(margin, 16.0))
+            .show(&self.ctx, |ui| {
+                ui.vertical(|ui| {
+                    let title_text = RichText::new("Settings").size(32.0).color(Color32::WHITE);
+                    ui.label(title_text);
+                    //Save settings and return to main menu
+                    let main_menu = new_button(ui, "Main Menu", 16.0, GuiAction::SaveSettings);
+                    action = update_action(action, main_menu);
+                    ui.add_space(24.0);
+                    settings_gui(gamestate, ui);
+                });
+            });
+
+        //End frame
+        let egui::FullOutput {
+            platform_output,
+            textures_delta,
+            shapes,
+            pixels_per_point: _,
+            viewport_output: _,
+        } = self.ctx.end_pass();
+
+        //Handle copy pasting
+        for command in platform_output.commands {
+            if let egui::OutputCommand::CopyText(copied_text) = command {
+                egui_backend::copy_to_clipboard(&mut self.input_state, copied_text);
+            }
+        }
+
+        //Display
+        let clipped_shapes = self.ctx.tessellate(shapes, pixels_per_point);
+        self.painter
+            .paint_and_update_textures(pixels_per_point, &clipped_shapes, &textures_delta);
+
+        action
+    }
+
     //Display gui for load set menu
     pub fn display_load_screen(&mut self, gamestate: &mut Game) -> Option<GuiAction> {
         let mut action = None;
@@ -512,26 +738,9 @@ impl GuiController {
                         .max_height(height - 180.0)
                         .show(ui, |ui| {
                             for (set, dir) in &gamestate.set_paths {
-                                //Replace underscores with spaces and capitalize each word
-                                let text = set
-                                    .split("_")
-                                    .map(|word| {
-                                        let mut chars = word.chars();
-                                        match chars.next() {
-                                            Some(first) => {
-                                                first
-                                                    .to_uppercase()
-                                                    .next()
-                                                    .unwrap_or(first)
-                                                    .to_string()
-                                                    + chars.as_str()
-                                            }
-                                            None => String::new(),
-                                        }
-                                    })
-                                    .collect::<Vec<String>>()
-                                    .join(" ");
-                                let text = RichText::new(text).size(16.0).color(Color32::WHITE);
+                                let text = RichText::new(display_name(set))
+                                    .size(16.0)
+                                    .color(Color32::WHITE);
                                 ui.selectable_value(
                                     &mut gamestate.selected_set_path,
                                     format!("{}/{}", dir.clone(), set.clone()),
@@ -546,12 +755,57 @@ impl GuiController {
                     //Start learn
                     let load = new_button(ui, "Learn", 16.0, GuiAction::GotoLearn);
                     action = update_action(action, load);
+                    //Start matching
+                    let load = new_button(ui, "Match", 16.0, GuiAction::GotoMatch);
+                    action = update_action(action, load);
+                    //Start an exam
+                    let load = new_button(ui, "Exam", 16.0, GuiAction::GotoExam);
+                    action = update_action(action, load);
                     //Return to main menu
                     let main_menu = new_button(ui, "Main Menu", 16.0, GuiAction::GotoMainMenu);
                     action = update_action(action, main_menu);
+                    ui.add_space(8.0);
+                    let options_text = RichText::new("Asteroids Options").color(Color32::WHITE);
+                    egui::CollapsingHeader::new(options_text).show(ui, |ui| {
+                        asteroids_options_gui(ui, &gamestate.difficulties, &mut gamestate.settings);
+                    });
+                    let options_text = RichText::new("Learn Options").color(Color32::WHITE);
+                    egui::CollapsingHeader::new(options_text).show(ui, |ui| {
+                        learn_options_gui(ui, &mut gamestate.settings.learn);
+                    });
+                    let options_text = RichText::new("Exam Options").color(Color32::WHITE);
+                    egui::CollapsingHeader::new(options_text).show(ui, |ui| {
+                        exam_options_gui(ui, &mut gamestate.settings.exam);
+                    });
+                    let scores_text = RichText::new("High Scores").color(Color32::WHITE);
+                    egui::CollapsingHeader::new(scores_text).show(ui, |ui| {
+                        load_screen_high_scores(gamestate, ui);
+                    });
                 });
             });
 
+        //Ask whether to resume a saved learn session
+        if gamestate.learn_resume_prompt.as_ref() == Some(&gamestate.selected_set_path) {
+            egui::Window::new("resume_learn")
+                .movable(false)
+                .title_bar(false)
+                .resizable(false)
+                .anchor(Align2::CENTER_CENTER, vec2(0.0, 0.0))
+                .show(&self.ctx, |ui| {
+                    ui.vertical_centered(|ui| {
+                        let text = RichText::new("You have an unfinished session for this set.")
+                            .size(16.0)
+                            .color(Color32::WHITE);
+                        ui.label(text);
+                        ui.add_space(8.0);
+                        let resume = new_button(ui, "Resume", 16.0, GuiAction::ResumeLearn);
+                        action = update_action(action, resume);
+                        let restart = new_button(ui, "Start over", 16.0, GuiAction::RestartLearn);
+                        action = update_action(action, restart);
+                    });
+                });
+        }
+
         //End frame
         let egui::FullOutput {
             platform_output,
@@ -602,13 +856,183 @@ impl GuiController {
                     //Progress bar
                     let progress = ProgressBar::new(gamestate.learn_state.percent());
                     ui.add(progress);
-                    //Display question
-                    learn_question(gamestate, ui);
 
-                    if !gamestate.learn_state.mcq.is_empty() {
-                        mcq_gui(gamestate, ui);
-                    } else if !gamestate.learn_state.frq.is_empty() {
-                        frq_gui(gamestate, ui);
+                    if gamestate.learn_paused {
+                        let pause_action = learn_pause_gui(gamestate, ui);
+                        action = update_action(action, pause_action);
+                        return;
+                    }
+
+                    if let Some(due) = gamestate.learn_state.next_due {
+                        let study_all = nothing_due_gui(due, ui);
+                        action = update_action(action, study_all);
+                    } else {
+                        //Display question
+                        learn_question(gamestate, ui);
+
+                        match gamestate.learn_state.question_type() {
+                            Some(QuestionType::MultipleChoice) => mcq_gui(gamestate, ui),
+                            Some(QuestionType::TrueFalse) => tf_gui(gamestate, ui),
+                            Some(QuestionType::FreeResponse) => frq_gui(gamestate, ui),
+                            None => {
+                                let study_missed = learn_summary_gui(gamestate, ui);
+                                action = update_action(action, study_missed);
+                            }
+                        }
+                    }
+
+                    ui.add_space(32.0);
+                    let main_menu = new_button(ui, "Main Menu", 16.0, GuiAction::LeaveLearn);
+                    action = update_action(action, main_menu);
+                });
+            });
+
+        //End frame
+        let egui::FullOutput {
+            platform_output,
+            textures_delta,
+            shapes,
+            pixels_per_point: _,
+            viewport_output: _,
+        } = self.ctx.end_pass();
+
+        //Handle copy pasting
+        for command in platform_output.commands {
+            if let egui::OutputCommand::CopyText(copied_text) = command {
+                egui_backend::copy_to_clipboard(&mut self.input_state, copied_text);
+            }
+        }
+
+        //Display
+        let clipped_shapes = self.ctx.tessellate(shapes, pixels_per_point);
+        self.painter
+            .paint_and_update_textures(pixels_per_point, &clipped_shapes, &textures_delta);
+
+        action
+    }
+
+    //Display gui for matching
+    pub fn display_match_gui(&mut self, gamestate: &mut Game) -> Option<GuiAction> {
+        let mut action = None;
+        let (w, h) = gamestate.get_window_size();
+
+        let pixels_per_point = self.input_state.pixels_per_point;
+        if self.ctx.pixels_per_point() != pixels_per_point {
+            self.ctx.set_pixels_per_point(pixels_per_point);
+        }
+        self.ctx.begin_pass(self.input_state.input.take());
+
+        let width = w as f32 / pixels_per_point - 64.0;
+        let height = h as f32 / pixels_per_point - 64.0;
+        egui::Window::new("match")
+            .movable(false)
+            .title_bar(false)
+            .scroll(true)
+            .fixed_size(vec2(width, height))
+            .fixed_pos(Pos2::new(24.0, 24.0))
+            .show(&self.ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    let heading = RichText::new("Match").size(28.0).color(Color32::WHITE);
+                    ui.heading(heading);
+                    //Progress bar
+                    let progress = ProgressBar::new(gamestate.match_state.percent());
+                    ui.add(progress);
+                    let match_state = &gamestate.match_state;
+                    let stats = format!(
+                        "Score: {}    Time: {:.0}s    Mistakes: {}",
+                        match_state.score, match_state.time, match_state.mistakes
+                    );
+                    ui.label(RichText::new(stats).size(16.0).color(Color32::WHITE));
+                    ui.add_space(16.0);
+
+                    if gamestate.match_state.finished() {
+                        let msg = RichText::new("You Finished!")
+                            .size(28.0)
+                            .color(Color32::GREEN);
+                        ui.label(msg);
+                        let highlight = gamestate.new_high_score;
+                        high_score_table(ui, &gamestate.high_scores, ScoreMode::Match, highlight);
+                        ui.add_space(8.0);
+                        let play_again = new_button(ui, "Play Again", 16.0, GuiAction::GotoMatch);
+                        action = update_action(action, play_again);
+                    } else {
+                        match_gui(&mut gamestate.match_state, ui, width);
+                    }
+
+                    ui.add_space(32.0);
+                    let main_menu = new_button(ui, "Main Menu", 16.0, GuiAction::GotoMainMenu);
+                    action = update_action(action, main_menu);
+                });
+            });
+
+        //End frame
+        let egui::FullOutput {
+            platform_output,
+            textures_delta,
+            shapes,
+            pixels_per_point: _,
+            viewport_output: _,
+        } = self.ctx.end_pass();
+
+        //Handle copy pasting
+        for command in platform_output.commands {
+            if let egui::OutputCommand::CopyText(copied_text) = command {
+                egui_backend::copy_to_clipboard(&mut self.input_state, copied_text);
+            }
+        }
+
+        //Display
+        let clipped_shapes = self.ctx.tessellate(shapes, pixels_per_point);
+        self.painter
+            .paint_and_update_textures(pixels_per_point, &clipped_shapes, &textures_delta);
+
+        action
+    }
+
+    //Display gui for exams
+    pub fn display_exam_gui(&mut self, gamestate: &mut Game) -> Option<GuiAction> {
+        let mut action = None;
+        let (w, h) = gamestate.get_window_size();
+
+        let pixels_per_point = self.input_state.pixels_per_point;
+        if self.ctx.pixels_per_point() != pixels_per_point {
+            self.ctx.set_pixels_per_point(pixels_per_point);
+        }
+        self.ctx.begin_pass(self.input_state.input.take());
+
+        let width = w as f32 / pixels_per_point - 64.0;
+        let height = h as f32 / pixels_per_point - 64.0;
+        egui::Window::new("exam")
+            .movable(false)
+            .title_bar(false)
+            .scroll(true)
+            .fixed_size(vec2(width, height))
+            .fixed_pos(Pos2::new(24.0, 24.0))
+            .show(&self.ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    let heading = RichText::new("Exam").size(28.0).color(Color32::WHITE);
+                    ui.heading(heading);
+                    //Progress bar
+                    let progress = ProgressBar::new(gamestate.exam_state.percent());
+                    ui.add(progress);
+                    let exam_state = &gamestate.exam_state;
+                    let mut stats = format!(
+                        "Question {} of {}",
+                        (exam_state.current + 1).min(exam_state.size()),
+                        exam_state.size()
+                    );
+                    if let Some(time_left) = exam_state.time_left() {
+                        let secs = time_left.ceil() as u32;
+                        stats.push_str(&format!("    Time left: {}:{:02}", secs / 60, secs % 60));
+                    }
+                    ui.label(RichText::new(stats).size(16.0).color(Color32::WHITE));
+                    ui.add_space(16.0);
+
+                    if gamestate.exam_state.finished() {
+                        let report = exam_report_gui(&gamestate.exam_state, ui);
+                        action = update_action(action, report);
+                    } else {
+                        exam_gui(gamestate, ui);
                     }
 
                     ui.add_space(32.0);
@@ -644,24 +1068,37 @@ impl GuiController {
 
 pub fn handle_gui_action(gamestate: &mut Game, action: GuiAction) {
     match action {
-        GuiAction::Restart => gamestate.restart(),
-        GuiAction::GotoMainMenu => gamestate.current_screen = GameScreen::MainMenu,
+        GuiAction::Restart => match gamestate.playback.take() {
+            Some(replay) => gamestate.start_playback(replay),
+            None => gamestate.start_round(),
+        },
+        GuiAction::GotoMainMenu => {
+            gamestate.save_replay();
+            gamestate.current_screen = GameScreen::MainMenu;
+        }
         GuiAction::GotoAbout => gamestate.current_screen = GameScreen::About,
         GuiAction::GotoLoadFlashcards => {
             gamestate.current_screen = GameScreen::LoadFlashcards;
             gamestate.get_set_list();
             gamestate.selected_set_path.clear();
+            gamestate.learn_resume_prompt = None;
         }
         GuiAction::Load => {
             if gamestate.selected_set_path.is_empty() {
                 return;
             }
+            //Save any changes to the asteroid options
+            gamestate.settings.save();
             gamestate.restart();
             let path = vec![gamestate.selected_set_path.clone()];
-            gamestate.flashcards = flashcards::load_flashcards(&path);
+            gamestate.set_flashcards(flashcards::load_flashcards(&path));
             if gamestate.flashcards.is_empty() {
                 return;
             }
+            let player = &gamestate.settings.player_name;
+            let progress = Progress::load(player, &gamestate.selected_set_path);
+            gamestate.sim.selector.set_progress(&progress);
+            gamestate.start_round();
             gamestate.current_screen = GameScreen::Game;
         }
         GuiAction::ToggleMute => gamestate.audio.toggle_mute(),
@@ -669,18 +1106,493 @@ pub fn handle_gui_action(gamestate: &mut Game, action: GuiAction) {
             if gamestate.selected_set_path.is_empty() {
                 return;
             }
+            //Ask the player if they want to resume a saved session
+            if std::path::Path::new(&learn_save_path(gamestate)).exists() {
+                gamestate.learn_resume_prompt = Some(gamestate.selected_set_path.clone());
+                return;
+            }
+            start_learn(gamestate, false);
+        }
+        GuiAction::StudyMissed => {
+            let missed: Vec<Flashcard> = gamestate
+                .learn_state
+                .most_missed()
+                .into_iter()
+                .map(|stats| stats.card)
+                .collect();
+            if missed.is_empty() {
+                return;
+            }
+            let seed = gamestate.next_seed();
+            let config = &gamestate.settings.learn;
+            gamestate.learn_state =
+                LearnState::review_missed(&missed, &gamestate.flashcards, config, seed);
+            gamestate.learn_state.prepare_question();
+        }
+        GuiAction::StudyAll => {
+            let seed = gamestate.next_seed();
+            let config = &gamestate.settings.learn;
+            gamestate.learn_state = LearnState::study_all(&gamestate.flashcards, config, seed);
+            gamestate.learn_state.prepare_question();
+        }
+        GuiAction::ResumeLearn => start_learn(gamestate, true),
+        GuiAction::RestartLearn => start_learn(gamestate, false),
+        GuiAction::LeaveLearn => {
+            gamestate.learn_state.save();
+            gamestate.current_screen = GameScreen::MainMenu;
+        }
+        GuiAction::GotoMatch => {
+            if gamestate.selected_set_path.is_empty() {
+                return;
+            }
+            gamestate.restart();
+            let path = vec![gamestate.selected_set_path.clone()];
+            gamestate.set_flashcards(flashcards::load_flashcards(&path));
+            let player = &gamestate.settings.player_name;
+            let progress = Progress::load(player, &gamestate.selected_set_path);
+            let seed = gamestate.next_seed();
+            gamestate.match_state = MatchState::new(&gamestate.flashcards, progress, seed);
+            gamestate.current_screen = GameScreen::Match;
+        }
+        GuiAction::GotoExam => {
+            if gamestate.selected_set_path.is_empty() {
+                return;
+            }
+            //Save any changes to the exam options
+            gamestate.settings.save();
             gamestate.restart();
             let path = vec![gamestate.selected_set_path.clone()];
-            gamestate.flashcards = flashcards::load_flashcards(&path);
+            gamestate.set_flashcards(flashcards::load_flashcards(&path));
             gamestate.answer.clear();
-            gamestate.current_screen = GameScreen::Learn;
-            gamestate.learn_state = LearnState::new(&gamestate.flashcards);
-            gamestate.learn_state.set_mcq_ans();
+            let config = &gamestate.settings.exam;
+            gamestate.exam_state =
+                ExamState::new(&gamestate.flashcards, config, gamestate.sim.rng());
+            gamestate.current_screen = GameScreen::Exam;
+        }
+        GuiAction::SaveReport(format) => {
+            let player = &gamestate.settings.player_name;
+            let set_path = &gamestate.selected_set_path;
+            gamestate.exam_state.save_report(format, player, set_path);
+        }
+        GuiAction::GotoSettings => gamestate.current_screen = GameScreen::Settings,
+        GuiAction::SaveSettings => {
+            gamestate.settings.save();
+            gamestate.current_screen = GameScreen::MainMenu;
         }
         GuiAction::Quit => std::process::exit(0),
     }
 }
 
+//Path to the saved learn session for the selected set
+fn learn_save_path(gamestate: &Game) -> String {
+    let player = &gamestate.settings.player_name;
+    progress::player_set_path("saves", player, &gamestate.selected_set_path)
+}
+
+//Starts learn mode on the selected set, if `resume` is true then the saved
+//session is loaded (if there is one)
+fn start_learn(gamestate: &mut Game, resume: bool) {
+    if gamestate.selected_set_path.is_empty() {
+        return;
+    }
+    gamestate.learn_resume_prompt = None;
+    //Save any changes to the learn options
+    gamestate.settings.save();
+    gamestate.restart();
+    let path = vec![gamestate.selected_set_path.clone()];
+    gamestate.set_flashcards(flashcards::load_flashcards(&path));
+    gamestate.answer.clear();
+    gamestate.current_screen = GameScreen::Learn;
+    gamestate.learn_paused = false;
+
+    let save_path = learn_save_path(gamestate);
+    let seed = gamestate.next_seed();
+    let player = &gamestate.settings.player_name;
+    let set_path = &gamestate.selected_set_path;
+    let config = &gamestate.settings.learn;
+    let saved = if resume {
+        let progress = Progress::load(player, set_path);
+        LearnState::load(&gamestate.flashcards, progress, config, &save_path, seed)
+    } else {
+        None
+    };
+
+    gamestate.learn_state = match saved {
+        Some(learn_state) => learn_state,
+        None => {
+            learn::delete_save(&save_path);
+            let progress = Progress::load(player, set_path);
+            let mut learn_state =
+                LearnState::new(&gamestate.flashcards, progress, config, &save_path, seed);
+            learn_state.prepare_question();
+            learn_state
+        }
+    };
+}
+
+//Display the questions and answers of a matching round in two columns
+fn match_gui(match_state: &mut MatchState, ui: &mut Ui, width: f32) {
+    let mut clicked_question = None;
+    let mut clicked_answer = None;
+    ui.columns(2, |columns| {
+        for (i, card) in match_state.round.iter().enumerate() {
+            let col = if match_state.matched_questions[i] {
+                Color32::GREEN
+            } else if match_state.wrong_match.is_some_and(|(q, _)| q == i) {
+                Color32::RED
+            } else {
+                Color32::WHITE
+            };
+            let text = RichText::new(&card.question).size(16.0).color(col);
+            let selected = match_state.selected == Some(i);
+            let button = egui::Button::new(text)
+                .selected(selected)
+                .min_size(vec2(width / 3.0, 0.0));
+            if columns[0].add(button).clicked() {
+                clicked_question = Some(i);
+            }
+        }
+
+        for &i in &match_state.answer_order {
+            let col = if match_state.matched_answers[i] {
+                Color32::GREEN
+            } else if match_state.wrong_match.is_some_and(|(_, a)| a == i) {
+                Color32::RED
+            } else {
+                Color32::WHITE
+            };
+            let text = RichText::new(&match_state.round[i].answer)
+                .size(16.0)
+                .color(col);
+            let button = egui::Button::new(text).min_size(vec2(width / 3.0, 0.0));
+            if columns[1].add(button).clicked() {
+                clicked_answer = Some(i);
+            }
+        }
+    });
+
+    if let Some(question) = clicked_question {
+        match_state.select(question);
+    }
+
+    if let Some(answer) = clicked_answer {
+        match_state.match_answer(answer);
+    }
+}
+
+//Display the options for how a learn session is structured
+fn learn_options_gui(ui: &mut Ui, config: &mut LearnConfig) {
+    ui.add(
+        egui::Slider::new(&mut config.mcq_reps, 0..=learn::MAX_REPS).text("Multiple choice rounds"),
+    );
+    ui.add(egui::Slider::new(&mut config.tf_reps, 0..=learn::MAX_REPS).text("True/false rounds"));
+    ui.add(
+        egui::Slider::new(&mut config.frq_reps, 0..=learn::MAX_REPS).text("Free response rounds"),
+    );
+    let option_range = learn::MIN_MCQ_OPTIONS..=learn::MAX_MCQ_OPTIONS;
+    ui.add(
+        egui::Slider::new(&mut config.mcq_options, option_range).text("Multiple choice options"),
+    );
+    ui.checkbox(
+        &mut config.wait_for_enter,
+        "Press enter to continue after each answer",
+    );
+    let feedback_range = learn::MIN_FEEDBACK_TIME..=learn::MAX_FEEDBACK_TIME;
+    ui.add_enabled(
+        !config.wait_for_enter,
+        egui::Slider::new(&mut config.feedback_time, feedback_range)
+            .text("Feedback time (seconds)"),
+    );
+    let cap_range = 0..=learn::MAX_SESSION_CAP;
+    ui.add(egui::Slider::new(&mut config.session_cap, cap_range).text("Max cards (0 = no limit)"));
+    ui.label("Missed cards go to:");
+    ui.radio_value(
+        &mut config.requeue,
+        RequeueMode::Back,
+        "The back of the queue",
+    );
+    ui.radio_value(&mut config.requeue, RequeueMode::Later, "A few cards later");
+    let offset_range = learn::MIN_REQUEUE_OFFSET..=learn::MAX_REQUEUE_OFFSET;
+    ui.add_enabled(
+        config.requeue == RequeueMode::Later,
+        egui::Slider::new(&mut config.requeue_offset, offset_range).text("Cards later"),
+    );
+}
+
+//Replace underscores with spaces and capitalize each word
+fn display_name(name: &str) -> String {
+    name.split("_")
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => {
+                    first.to_uppercase().next().unwrap_or(first).to_string() + chars.as_str()
+                }
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn asteroids_options_gui(ui: &mut Ui, presets: &[(String, Difficulty)], settings: &mut Settings) {
+    ui.label("Difficulty:");
+    ui.horizontal(|ui| {
+        for (name, _) in presets {
+            ui.radio_value(&mut settings.difficulty, name.clone(), display_name(name));
+        }
+        ui.radio_value(
+            &mut settings.difficulty,
+            CUSTOM_DIFFICULTY.to_string(),
+            "Custom",
+        );
+    });
+
+    if settings.difficulty != CUSTOM_DIFFICULTY {
+        return;
+    }
+    let custom = &mut settings.custom_difficulty;
+    ui.add(egui::Slider::new(&mut custom.health, 1..=10).text("Health"));
+    ui.add(
+        egui::Slider::new(&mut custom.spawn_interval, 2.0..=20.0).text("Seconds between asteroids"),
+    );
+    ui.add(
+        egui::Slider::new(&mut custom.fall_time, 5.0..=60.0)
+            .text("Seconds for an asteroid to fall on level 1"),
+    );
+    ui.add(
+        egui::Slider::new(&mut custom.third_asteroid_level, 1..=20)
+            .text("Level with three asteroids at once"),
+    );
+    if ui.button("Reset to Normal").clicked() {
+        *custom = Difficulty::new();
+    }
+}
+
+//Displays the top scores of a mode, the row at `highlight` is highlighted
+fn high_score_table(ui: &mut Ui, scores: &HighScores, mode: ScoreMode, highlight: Option<usize>) {
+    let heading = format!("{} High Scores", mode.name());
+    ui.label(RichText::new(heading).size(20.0).color(Color32::WHITE));
+    let table = scores.get(mode);
+    if table.is_empty() {
+        ui.label(
+            RichText::new("No scores yet")
+                .size(14.0)
+                .color(Color32::WHITE),
+        );
+        return;
+    }
+
+    egui::Grid::new(format!("high_scores_{}", mode.name()))
+        .striped(true)
+        .spacing(vec2(16.0, 4.0))
+        .show(ui, |ui| {
+            for heading in ["#", "Player", "Score", "Level", "Accuracy", "Date"] {
+                ui.label(RichText::new(heading).size(14.0).color(Color32::WHITE));
+            }
+            ui.end_row();
+            for (i, entry) in table.iter().enumerate() {
+                let col = if highlight == Some(i) {
+                    Color32::YELLOW
+                } else {
+                    Color32::WHITE
+                };
+                let level = if entry.level > 0 {
+                    entry.level.to_string()
+                } else {
+                    "-".to_string()
+                };
+                let row = [
+                    (i + 1).to_string(),
+                    entry.player.clone(),
+                    entry.score.to_string(),
+                    level,
+                    format!("{:.0}%", entry.accuracy * 100.0),
+                    highscores::format_date(entry.date),
+                ];
+                for text in row {
+                    ui.label(RichText::new(text).size(14.0).color(col));
+                }
+                ui.end_row();
+            }
+        });
+}
+
+//Displays the high scores of the selected set on the load screen
+fn load_screen_high_scores(gamestate: &mut Game, ui: &mut Ui) {
+    if gamestate.selected_set_path.is_empty() {
+        ui.label("Select a set to see its high scores.");
+        return;
+    }
+
+    if gamestate.high_scores.set_path != gamestate.selected_set_path {
+        gamestate.high_scores = HighScores::load(&gamestate.selected_set_path);
+        gamestate.new_high_score = None;
+    }
+    high_score_table(ui, &gamestate.high_scores, ScoreMode::Asteroids, None);
+    ui.add_space(8.0);
+    high_score_table(ui, &gamestate.high_scores, ScoreMode::Match, None);
+}
+
+fn exam_options_gui(ui: &mut Ui, config: &mut ExamConfig) {
+    ui.add(
+        egui::Slider::new(&mut config.question_count, 0..=exam::MAX_EXAM_QUESTIONS)
+            .text("Questions (0 = every card)"),
+    );
+    ui.add(
+        egui::Slider::new(&mut config.time_limit, 0..=exam::MAX_TIME_LIMIT)
+            .text("Time limit in minutes (0 = no limit)"),
+    );
+}
+
+//Display the current exam question and answer box
+fn exam_gui(gamestate: &mut Game, ui: &mut Ui) {
+    let Some(card) = gamestate.exam_state.get_flashcard() else {
+        return;
+    };
+
+    let q = RichText::new(&card.question)
+        .size(24.0)
+        .color(Color32::WHITE);
+    ui.label(q);
+    answer_input(ui, &mut gamestate.answer);
+    char_palette(ui, &mut gamestate.answer, &gamestate.char_palette);
+    ui.horizontal(|ui| {
+        let submit = RichText::new("Submit").color(Color32::WHITE);
+        if ui.button(submit).clicked() {
+            gamestate.exam_state.submit(&gamestate.answer);
+            gamestate.answer.clear();
+        }
+        let finish = RichText::new("Finish Exam").color(Color32::WHITE);
+        if ui.button(finish).clicked() {
+            gamestate.exam_state.finish();
+        }
+    });
+}
+
+//Display the grade and a breakdown of each question at the end of an exam
+fn exam_report_gui(exam_state: &ExamState, ui: &mut Ui) -> Option<GuiAction> {
+    let mut action = None;
+
+    let grade = exam_state.grade();
+    let col = if grade >= 70.0 {
+        Color32::GREEN
+    } else {
+        Color32::RED
+    };
+    let text = RichText::new(format!("Grade: {grade:.1}%"))
+        .size(28.0)
+        .color(col);
+    ui.label(text);
+    let text = format!(
+        "{} of {} correct in {:.0} seconds",
+        exam_state.correct_count(),
+        exam_state.size(),
+        exam_state.time
+    );
+    ui.label(RichText::new(text).size(16.0).color(Color32::WHITE));
+    ui.add_space(16.0);
+
+    egui::Grid::new("exam_report")
+        .striped(true)
+        .spacing(vec2(16.0, 4.0))
+        .show(ui, |ui| {
+            for heading in ["#", "Question", "Your answer", "Correct answer"] {
+                ui.label(RichText::new(heading).size(14.0).color(Color32::WHITE));
+            }
+            ui.end_row();
+            for (i, res) in exam_state.results().iter().enumerate() {
+                let col = if res.correct {
+                    Color32::GREEN
+                } else {
+                    Color32::RED
+                };
+                ui.label(RichText::new((i + 1).to_string()).size(14.0).color(col));
+                ui.label(RichText::new(&res.card.question).size(14.0).color(col));
+                ui.label(RichText::new(&res.answer).size(14.0).color(col));
+                ui.label(RichText::new(&res.card.answer).size(14.0).color(col));
+                ui.end_row();
+            }
+        });
+
+    ui.add_space(16.0);
+    ui.horizontal(|ui| {
+        let formats = [
+            ("Save as Markdown", ReportFormat::Markdown),
+            ("Save as HTML", ReportFormat::Html),
+            ("Save as CSV", ReportFormat::Csv),
+        ];
+        for (text, format) in formats {
+            let save = new_button(ui, text, 16.0, GuiAction::SaveReport(format));
+            action = update_action(action, save);
+        }
+    });
+    if !exam_state.report_message.is_empty() {
+        ui.label(RichText::new(&exam_state.report_message).color(Color32::WHITE));
+    }
+    action
+}
+
+//Display the options in the settings menu
+fn settings_gui(gamestate: &mut Game, ui: &mut Ui) {
+    let settings = &mut gamestate.settings;
+
+    let heading = RichText::new("Player").size(20.0).color(Color32::WHITE);
+    ui.label(heading);
+    ui.horizontal(|ui| {
+        let name_text = RichText::new("Name:").size(16.0).color(Color32::WHITE);
+        ui.label(name_text);
+        if ui.text_edit_singleline(&mut settings.player_name).changed() {
+            settings.player_name = settings::clean_player_name(&settings.player_name);
+        }
+    });
+    ui.add_space(16.0);
+
+    let heading = RichText::new("Typing").size(20.0).color(Color32::WHITE);
+    ui.label(heading);
+    let compose_text = RichText::new("Compose accented characters (e.g. e' becomes é)")
+        .size(16.0)
+        .color(Color32::WHITE);
+    ui.checkbox(&mut settings.compose_enabled, compose_text);
+    ui.add_enabled_ui(settings.compose_enabled, |ui| {
+        let selected = if settings.compose_language.is_empty() {
+            "None"
+        } else {
+            &settings.compose_language
+        };
+        egui::ComboBox::from_label("Language")
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                for table in &gamestate.compose_tables {
+                    ui.selectable_value(
+                        &mut settings.compose_language,
+                        table.language.clone(),
+                        &table.language,
+                    );
+                }
+            });
+    });
+    ui.add_space(16.0);
+
+    let heading = RichText::new("Asteroids").size(20.0).color(Color32::WHITE);
+    ui.label(heading);
+    ui.label(RichText::new("Cards on asteroids are picked:").size(16.0));
+    let options = [
+        (CardSelection::Uniform, "At random"),
+        (
+            CardSelection::Weighted,
+            "More often if they are missed, slow or new",
+        ),
+        (
+            CardSelection::ShuffleBag,
+            "Every card once before any card repeats",
+        ),
+    ];
+    for (selection, text) in options {
+        ui.radio_value(&mut settings.card_selection, selection, text);
+    }
+}
+
 //Display learn gui
 pub fn learn_question(gamestate: &mut Game, ui: &mut Ui) {
     if let Some(flashcard) = gamestate.learn_state.get_flashcard() {
@@ -703,7 +1615,9 @@ pub fn mcq_gui(gamestate: &mut Game, ui: &mut Ui) {
         .get_flashcard()
         .unwrap_or(Flashcard::none());
     let mut clicked = false;
-    for ans in &gamestate.learn_state.mcq_ans {
+    for (i, ans) in gamestate.learn_state.mcq_ans.iter().enumerate() {
+        //Number key that selects this option
+        let label = format!("{}. {}", (i + 1) % 10, ans);
         let text = if gamestate.learn_state.display_correct() {
             //Change color of text if we are displaying the correct answer
             let col = if card.answer == *ans {
@@ -711,9 +1625,9 @@ pub fn mcq_gui(gamestate: &mut Game, ui: &mut Ui) {
             } else {
                 Color32::RED
             };
-            RichText::new(ans).size(12.0).color(col)
+            RichText::new(label).size(12.0).color(col)
         } else {
-            RichText::new(ans).size(12.0).color(Color32::WHITE)
+            RichText::new(label).size(12.0).color(Color32::WHITE)
         };
         if ui.button(text).clicked() {
             gamestate.answer = ans.to_string();
@@ -723,7 +1637,7 @@ pub fn mcq_gui(gamestate: &mut Game, ui: &mut Ui) {
 
     if gamestate.learn_state.display_correct() {
         //Display whether the user got it correct or not
-        let text = if gamestate.learn_state.answer == card.answer {
+        let text = if gamestate.learn_state.answered_correctly() {
             RichText::new("Correct!").color(Color32::GREEN)
         } else {
             RichText::new("Incorrect!").color(Color32::RED)
@@ -732,6 +1646,7 @@ pub fn mcq_gui(gamestate: &mut Game, ui: &mut Ui) {
         //Display the correct answer
         let answer_text = format!("The correct answer was: {}", card.answer);
         ui.label(answer_text);
+        continue_button(gamestate, ui);
     }
 
     if clicked {
@@ -740,6 +1655,128 @@ pub fn mcq_gui(gamestate: &mut Game, ui: &mut Ui) {
     }
 }
 
+//Displayed instead of the question when the player pauses learn mode
+fn learn_pause_gui(gamestate: &mut Game, ui: &mut Ui) -> Option<GuiAction> {
+    ui.add_space(16.0);
+    let heading = RichText::new("Paused").size(24.0).color(Color32::WHITE);
+    ui.label(heading);
+    ui.label("Press Esc to resume.");
+    ui.add_space(8.0);
+    let resume = RichText::new("Resume").size(16.0).color(Color32::WHITE);
+    if ui.button(resume).clicked() {
+        gamestate.learn_paused = false;
+    }
+    new_button(ui, "Save and Leave", 16.0, GuiAction::LeaveLearn)
+}
+
+//Displayed instead of a session when no cards are due for review
+fn nothing_due_gui(due: u64, ui: &mut Ui) -> Option<GuiAction> {
+    let date = highscores::format_date(due);
+    let text = RichText::new(format!("Nothing is due for review until {date}"))
+        .size(20.0)
+        .color(Color32::WHITE);
+    ui.label(text);
+    ui.label("Studying now does not change when cards are due.");
+    ui.add_space(8.0);
+    new_button(ui, "Study all anyway", 16.0, GuiAction::StudyAll)
+}
+
+//Maximum number of missed cards listed in the learn summary
+const SUMMARY_MISSED_COUNT: usize = 10;
+
+//Display a summary of a finished learn session
+pub fn learn_summary_gui(gamestate: &Game, ui: &mut Ui) -> Option<GuiAction> {
+    let learn_state = &gamestate.learn_state;
+    let mut lines = vec![];
+    let question_types = [
+        ("Multiple choice", QuestionType::MultipleChoice),
+        ("True/false", QuestionType::TrueFalse),
+        ("Free response", QuestionType::FreeResponse),
+    ];
+    for (name, question_type) in question_types {
+        if let Some(accuracy) = learn_state.accuracy(question_type) {
+            lines.push(format!("{name} accuracy: {:.0}%", accuracy * 100.0));
+        }
+    }
+    let minutes = learn_state.elapsed as u32 / 60;
+    let seconds = learn_state.elapsed as u32 % 60;
+    lines.push(format!("Total time: {minutes}:{seconds:02}"));
+    let avg_time = learn_state.average_response_time();
+    lines.push(format!("Average response time: {avg_time:.1}s"));
+    display_lines(ui, &lines);
+
+    let missed = learn_state.most_missed();
+    if missed.is_empty() {
+        return None;
+    }
+
+    ui.add_space(16.0);
+    let heading = RichText::new("Most missed cards")
+        .size(20.0)
+        .color(Color32::WHITE);
+    ui.label(heading);
+    for stats in missed.iter().take(SUMMARY_MISSED_COUNT) {
+        let mut text = format!(
+            "{} = {} (missed {} of {} attempts",
+            stats.card.question, stats.card.answer, stats.misses, stats.attempts
+        );
+        if stats.skips > 0 {
+            text.push_str(&format!(", didn't know {}", stats.skips));
+        }
+        if stats.overrides > 0 {
+            text.push_str(&format!(", marked right {}", stats.overrides));
+        }
+        if stats.hints > 0 {
+            text.push_str(&format!(", {} hints", stats.hints));
+        }
+        text.push(')');
+        ui.label(RichText::new(text).size(14.0).color(Color32::RED));
+    }
+    ui.add_space(8.0);
+    new_button(ui, "Study missed cards again", 16.0, GuiAction::StudyMissed)
+}
+
+pub fn tf_gui(gamestate: &mut Game, ui: &mut Ui) {
+    let card = gamestate
+        .learn_state
+        .get_flashcard()
+        .unwrap_or(Flashcard::none());
+
+    //Display the answer that the player has to decide is true or false
+    let shown = RichText::new(format!("= {}", gamestate.learn_state.tf_shown))
+        .size(20.0)
+        .color(Color32::WHITE);
+    ui.label(shown);
+    ui.horizontal(|ui| {
+        let true_text = RichText::new("True (Left)")
+            .size(16.0)
+            .color(Color32::WHITE);
+        if ui.button(true_text).clicked() {
+            gamestate.learn_state.submit_true_false(true);
+        }
+        let false_text = RichText::new("False (Right)")
+            .size(16.0)
+            .color(Color32::WHITE);
+        if ui.button(false_text).clicked() {
+            gamestate.learn_state.submit_true_false(false);
+        }
+    });
+
+    if gamestate.learn_state.display_correct() {
+        //Display whether the user got it correct or not
+        let text = if gamestate.learn_state.answered_correctly() {
+            RichText::new("Correct!").color(Color32::GREEN)
+        } else {
+            RichText::new("Incorrect!").color(Color32::RED)
+        };
+        ui.label(text);
+        //Display the correct answer
+        let answer_text = format!("The correct answer was: {}", card.answer);
+        ui.label(answer_text);
+        continue_button(gamestate, ui);
+    }
+}
+
 pub fn frq_gui(gamestate: &mut Game, ui: &mut Ui) {
     //Display answer options
     let card = gamestate
@@ -747,17 +1784,48 @@ pub fn frq_gui(gamestate: &mut Game, ui: &mut Ui) {
         .get_flashcard()
         .unwrap_or(Flashcard::none());
 
-    ui.text_edit_singleline(&mut gamestate.answer);
-
-    let submit = RichText::new("Submit").color(Color32::WHITE);
-    if ui.button(submit).clicked() {
-        gamestate.learn_state.submit(&gamestate.answer);
-        gamestate.answer.clear();
+    if gamestate.learn_state.hints_used() && !gamestate.learn_state.display_correct() {
+        let hint = RichText::new(gamestate.learn_state.hint_text())
+            .size(20.0)
+            .monospace()
+            .color(Color32::YELLOW);
+        ui.label(hint);
+        let (words, letters) = gamestate.learn_state.answer_length();
+        let word_text = if words == 1 { "word" } else { "words" };
+        ui.label(format!("{words} {word_text}, {letters} letters"));
     }
 
+    answer_input(ui, &mut gamestate.answer);
+    char_palette(ui, &mut gamestate.answer, &gamestate.char_palette);
+
+    ui.horizontal(|ui| {
+        let submit = RichText::new("Submit").color(Color32::WHITE);
+        if ui.button(submit).clicked() {
+            gamestate.learn_state.submit(&gamestate.answer);
+            gamestate.answer.clear();
+        }
+
+        if !gamestate.learn_state.display_correct() {
+            let hint = RichText::new("Hint").color(Color32::WHITE);
+            let can_hint = gamestate.learn_state.can_hint();
+            if ui.add_enabled(can_hint, egui::Button::new(hint)).clicked() {
+                gamestate.learn_state.hint();
+            }
+            let skip = RichText::new("I don't know").color(Color32::WHITE);
+            if ui.button(skip).clicked() {
+                gamestate.learn_state.skip();
+                gamestate.answer.clear();
+            }
+        }
+    });
+
     if gamestate.learn_state.display_correct() {
         //Display whether the user got it correct or not
-        let text = if gamestate.learn_state.answer == card.answer {
+        let text = if gamestate.learn_state.skipped() {
+            RichText::new("Skipped").color(Color32::YELLOW)
+        } else if gamestate.learn_state.answered_correctly() && gamestate.learn_state.hints_used() {
+            RichText::new("Correct (with hints)").color(Color32::YELLOW)
+        } else if gamestate.learn_state.answered_correctly() {
             RichText::new("Correct!").color(Color32::GREEN)
         } else {
             RichText::new("Incorrect!").color(Color32::RED)
@@ -766,5 +1834,26 @@ pub fn frq_gui(gamestate: &mut Game, ui: &mut Ui) {
         //Display the correct answer
         let answer_text = format!("The correct answer was: {}", card.answer);
         ui.label(answer_text);
+        //Allow the player to accept an answer that does not exactly match
+        if gamestate.learn_state.can_override() {
+            let text = RichText::new("I was right").color(Color32::WHITE);
+            if ui.button(text).clicked() {
+                gamestate.learn_state.override_correct();
+            }
+        }
+        continue_button(gamestate, ui);
+    }
+}
+
+//If the correct answer is displayed until the player continues, display a
+//button to continue to the next question
+fn continue_button(gamestate: &mut Game, ui: &mut Ui) {
+    if !gamestate.learn_state.waiting_to_continue() {
+        return;
+    }
+
+    let text = RichText::new("Continue (Enter)").color(Color32::WHITE);
+    if ui.button(text).clicked() {
+        gamestate.learn_state.continue_to_next();
     }
 }
@@ -0,0 +1,180 @@
+/*
+ * Local high score tables, each set has a table for each mode that keeps the
+ * best scores of every player on this computer
+ * */
+
+use crate::{
+    impfile::{self, Entry},
+    progress, settings, userdata,
+};
+use std::path::Path;
+
+pub const MAX_HIGH_SCORES: usize = 10;
+const SECONDS_PER_DAY: u64 = 60 * 60 * 24;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ScoreMode {
+    Asteroids,
+    Match,
+}
+
+impl ScoreMode {
+    fn id(&self) -> &'static str {
+        match self {
+            Self::Asteroids => "asteroids",
+            Self::Match => "match",
+        }
+    }
+
+    fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "asteroids" => Some(Self::Asteroids),
+            "match" => Some(Self::Match),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Asteroids => "Asteroids",
+            Self::Match => "Match",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ScoreEntry {
+    pub player: String,
+    pub score: u64,
+    //0 for modes that do not have levels
+    pub level: u32,
+    //0.0 - 1.0
+    pub accuracy: f32,
+    //Seconds since the unix epoch
+    pub date: u64,
+}
+
+//Converts seconds since the unix epoch to a YYYY-MM-DD date (in UTC)
+pub fn format_date(time: u64) -> String {
+    //Algorithm from http://howardhinnant.github.io/date_algorithms.html
+    let days = (time / SECONDS_PER_DAY) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+pub struct HighScores {
+    //Path of the set these scores are for
+    pub set_path: String,
+    asteroids: Vec<ScoreEntry>,
+    matching: Vec<ScoreEntry>,
+}
+
+impl HighScores {
+    pub fn empty() -> Self {
+        Self {
+            set_path: String::new(),
+            asteroids: vec![],
+            matching: vec![],
+        }
+    }
+
+    fn file_path(set_path: &str) -> String {
+        let name = format!("highscores/{}.impfile", progress::set_key(set_path));
+        userdata::data_path(&name)
+    }
+
+    //Loads the high scores for a set, returns empty tables if none are saved
+    pub fn load(set_path: &str) -> Self {
+        let mut scores = Self::empty();
+        scores.set_path = set_path.to_string();
+        let path = Self::file_path(set_path);
+        if !Path::new(&path).exists() {
+            return scores;
+        }
+
+        for entry in impfile::parse_file(&path) {
+            if entry.get_name() != "score" {
+                continue;
+            }
+            let Some(mode) = ScoreMode::from_id(&entry.get_var("mode")) else {
+                continue;
+            };
+            let score = ScoreEntry {
+                player: entry.get_var("player"),
+                score: entry.get_var("score").parse().unwrap_or(0),
+                level: entry.get_var("level").parse().unwrap_or(0),
+                accuracy: entry.get_var("accuracy").parse().unwrap_or(0.0),
+                date: entry.get_var("date").parse().unwrap_or(0),
+            };
+            scores.table_mut(mode).push(score);
+        }
+        scores
+            .asteroids
+            .sort_by_key(|entry| std::cmp::Reverse(entry.score));
+        scores
+            .matching
+            .sort_by_key(|entry| std::cmp::Reverse(entry.score));
+        scores
+    }
+
+    pub fn save(&self) {
+        let mut entries = vec![];
+        for mode in [ScoreMode::Asteroids, ScoreMode::Match] {
+            for score in self.get(mode) {
+                let mut entry = Entry::new("score");
+                entry.add_string("mode", mode.id());
+                entry.add_string("player", &settings::clean_player_name(&score.player));
+                entry.add_string("score", &score.score.to_string());
+                entry.add_integer("level", score.level as i64);
+                entry.add_float("accuracy", score.accuracy);
+                entry.add_string("date", &score.date.to_string());
+                entries.push(entry);
+            }
+        }
+
+        let path = Self::file_path(&self.set_path);
+        if let Err(msg) = impfile::write_file(&path, &entries) {
+            eprintln!("Failed to save high scores: {path}");
+            eprintln!("{msg}");
+        }
+    }
+
+    pub fn get(&self, mode: ScoreMode) -> &[ScoreEntry] {
+        match mode {
+            ScoreMode::Asteroids => &self.asteroids,
+            ScoreMode::Match => &self.matching,
+        }
+    }
+
+    fn table_mut(&mut self, mode: ScoreMode) -> &mut Vec<ScoreEntry> {
+        match mode {
+            ScoreMode::Asteroids => &mut self.asteroids,
+            ScoreMode::Match => &mut self.matching,
+        }
+    }
+
+    //Adds a score to the table for `mode`, returns its position in the table
+    //or None if it is not in the top scores
+    pub fn add(&mut self, mode: ScoreMode, score: ScoreEntry) -> Option<usize> {
+        let table = self.table_mut(mode);
+        //Later scores are placed below earlier scores that are the same
+        let index = table
+            .iter()
+            .position(|entry| entry.score < score.score)
+            .unwrap_or(table.len());
+        if index >= MAX_HIGH_SCORES {
+            return None;
+        }
+        table.insert(index, score);
+        table.truncate(MAX_HIGH_SCORES);
+        Some(index)
+    }
+}
@@ -12,6 +12,8 @@ use std::str::Chars;
 pub struct Entry {
     name: String,
     variables: HashMap<String, String>,
+    //Variable names in the order they were added
+    order: Vec<String>,
 }
 
 pub type EntryList = Vec<Entry>;
@@ -22,20 +24,25 @@ impl Entry {
         Self {
             name: entry_name.to_string(),
             variables: HashMap::new(),
+            order: vec![],
         }
     }
 
     #[allow(dead_code)]
     pub fn from_vec(entry_name: &str, vars: Vec<(String, String)>) -> Self {
-        let mut variable_map = HashMap::new();
+        let mut entry = Self::new(entry_name);
         for (name, value) in vars {
-            variable_map.insert(name, value);
+            entry.insert(name, value);
         }
+        entry
+    }
 
-        Self {
-            name: entry_name.to_string(),
-            variables: variable_map,
+    //Sets a variable, a new variable goes after the existing ones
+    fn insert(&mut self, var_name: String, value: String) {
+        if !self.variables.contains_key(&var_name) {
+            self.order.push(var_name.clone());
         }
+        self.variables.insert(var_name, value);
     }
 
     //Returns the name of the entry
@@ -52,19 +59,19 @@ impl Entry {
     }
 
     pub fn add_string(&mut self, var_name: &str, s: &str) {
-        self.variables.insert(var_name.to_string(), s.to_string());
+        self.insert(var_name.to_string(), s.to_string());
     }
 
     pub fn add_float(&mut self, var_name: &str, v: f32) {
-        self.variables.insert(var_name.to_string(), v.to_string());
+        self.insert(var_name.to_string(), v.to_string());
     }
 
     pub fn add_bool(&mut self, var_name: &str, b: bool) {
-        self.variables.insert(var_name.to_string(), b.to_string());
+        self.insert(var_name.to_string(), b.to_string());
     }
 
     pub fn add_integer(&mut self, var_name: &str, i: i64) {
-        self.variables.insert(var_name.to_string(), i.to_string());
+        self.insert(var_name.to_string(), i.to_string());
     }
 
     //Converts the value into a formatted string, used for serializing to a file
@@ -75,11 +82,11 @@ impl Entry {
         res.push_str(&self.name);
         res.push_str("\" {\n");
         //variables
-        for (var_name, val) in &self.variables {
+        for (var_name, val) in self.get_var_list() {
             res.push_str("\t\"");
-            res.push_str(var_name);
+            res.push_str(&var_name);
             res.push_str("\" = \"");
-            res.push_str(val);
+            res.push_str(&val);
             res.push_str("\";\n");
         }
         res.push('}');
@@ -87,11 +94,12 @@ impl Entry {
         res
     }
 
-    //Returns a vector of variable names and the values
+    //Returns a vector of variable names and the values in the order they
+    //were added
     pub fn get_var_list(&self) -> Vec<(String, String)> {
-        self.variables
+        self.order
             .iter()
-            .map(|(name, val)| (name.clone(), val.clone()))
+            .map(|name| (name.clone(), self.get_var(name)))
             .collect()
     }
 }
@@ -174,6 +182,19 @@ pub fn write_comment(outfile: &mut File, comment_text: &str) {
     }
 }
 
+//Writes entries into a file, overwriting it if it already exists
+pub fn write_file(path: &str, entries: &[Entry]) -> Result<(), String> {
+    let mut outfile = File::create(path).map_err(|e| e.to_string())?;
+    for entry in entries {
+        let mut entry_str = entry.to_impfile_string();
+        entry_str.push_str("\n\n");
+        outfile
+            .write_all(entry_str.as_bytes())
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
 //Returns either Ok(name) or Err(msg)
 fn parse_name(file_chars: &mut Peekable<Chars>) -> Result<String, String> {
     let mut name = String::new();
@@ -276,7 +297,7 @@ fn parse_entry(file_chars: &mut Peekable<Chars>) -> Result<Entry, String> {
     while entry_content_chars.peek().is_some() {
         match parse_variable(&mut entry_content_chars) {
             Ok((name, value)) => {
-                entry.variables.insert(name, value);
+                entry.insert(name, value);
             }
             Err(msg) => {
                 let err = format!("Error in \'{}\': ", entry.name);
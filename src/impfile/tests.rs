@@ -91,3 +91,19 @@ fn test_add_variables() {
     assert_eq!(entry.get_var("test_bool2"), "false");
     assert_eq!(entry.get_var("test_float"), "1.23");
 }
+
+#[test]
+fn test_var_list_order() {
+    let mut entry = Entry::new("entry");
+    entry.add_string("b", "1");
+    entry.add_string("a", "2");
+    entry.add_string("c", "3");
+    entry.add_string("b", "4");
+    let names: Vec<String> = entry
+        .get_var_list()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    assert_eq!(names, ["b", "a", "c"]);
+    assert_eq!(entry.get_var("b"), "4");
+}
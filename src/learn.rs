@@ -1,43 +1,549 @@
-use crate::flashcards::Flashcard;
+mod tests;
+
+use crate::{
+    flashcards::Flashcard,
+    impfile::{self, Entry},
+    progress::{self, Progress},
+};
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use std::collections::{HashSet, VecDeque};
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
 
-//How long should we display the correct answer for the user?
+//How long should we display the correct answer for the user? (default)
 const DISPLAY_CORRECT_ANS_TIMER: f32 = 1.5;
+pub const MIN_MCQ_OPTIONS: usize = 2;
+pub const MAX_MCQ_OPTIONS: usize = 6;
+//Most times a card can be asked as each type of question
+pub const MAX_REPS: u32 = 3;
+pub const MIN_FEEDBACK_TIME: f32 = 0.5;
+pub const MAX_FEEDBACK_TIME: f32 = 5.0;
+pub const MAX_SESSION_CAP: usize = 200;
+pub const MIN_REQUEUE_OFFSET: usize = 1;
+pub const MAX_REQUEUE_OFFSET: usize = 10;
+//Highest review quality for a card that was answered using hints
+const HINT_REVIEW_QUALITY: u32 = 3;
+
+//Returns true if `answer` is a correct answer to `card`
+pub fn check_answer(card: &Flashcard, answer: &str) -> bool {
+    answer == card.answer
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuestionType {
+    MultipleChoice,
+    TrueFalse,
+    FreeResponse,
+}
+
+impl QuestionType {
+    //Used when saving a session
+    fn id(&self) -> &'static str {
+        match self {
+            Self::MultipleChoice => "mcq",
+            Self::TrueFalse => "tf",
+            Self::FreeResponse => "frq",
+        }
+    }
+
+    fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "mcq" => Some(Self::MultipleChoice),
+            "tf" => Some(Self::TrueFalse),
+            "frq" => Some(Self::FreeResponse),
+            _ => None,
+        }
+    }
+}
+
+//Statistics for a card in a learn session
+pub struct CardStats {
+    pub card: Flashcard,
+    pub attempts: u32,
+    pub misses: u32,
+    //Number of times the player marked their answer as correct
+    pub overrides: u32,
+    //Number of times the player did not know the answer
+    pub skips: u32,
+    //Number of letters revealed with hints
+    pub hints: u32,
+}
+
+//Where a card goes after it is answered incorrectly
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum RequeueMode {
+    //The back of the queue
+    Back,
+    //A few cards later (`LearnConfig::requeue_offset` cards)
+    Later,
+}
+
+//Options that control how a learn session is structured
+#[derive(Clone)]
+pub struct LearnConfig {
+    //How many times each card is asked as a multiple choice question
+    pub mcq_reps: u32,
+    //How many times each card is asked as a true/false question
+    pub tf_reps: u32,
+    //How many times each card is asked as a free response question
+    pub frq_reps: u32,
+    pub mcq_options: usize,
+    //How long the correct answer is displayed (in seconds)
+    pub feedback_time: f32,
+    //If true, the correct answer is displayed until enter is pressed
+    pub wait_for_enter: bool,
+    //Maximum number of cards in a session, 0 means there is no limit
+    pub session_cap: usize,
+    pub requeue: RequeueMode,
+    pub requeue_offset: usize,
+}
+
+impl LearnConfig {
+    pub fn new() -> Self {
+        Self {
+            mcq_reps: 1,
+            tf_reps: 0,
+            frq_reps: 2,
+            mcq_options: 4,
+            feedback_time: DISPLAY_CORRECT_ANS_TIMER,
+            wait_for_enter: false,
+            session_cap: 0,
+            requeue: RequeueMode::Back,
+            requeue_offset: 3,
+        }
+    }
+
+    pub fn from_entry(entry: &Entry) -> Self {
+        let default = Self::new();
+        Self {
+            mcq_reps: entry
+                .get_var("mcq_reps")
+                .parse()
+                .unwrap_or(default.mcq_reps)
+                .min(MAX_REPS),
+            tf_reps: entry
+                .get_var("tf_reps")
+                .parse()
+                .unwrap_or(default.tf_reps)
+                .min(MAX_REPS),
+            frq_reps: entry
+                .get_var("frq_reps")
+                .parse()
+                .unwrap_or(default.frq_reps)
+                .min(MAX_REPS),
+            mcq_options: entry
+                .get_var("mcq_options")
+                .parse()
+                .unwrap_or(default.mcq_options)
+                .clamp(MIN_MCQ_OPTIONS, MAX_MCQ_OPTIONS),
+            //NaN would never count down
+            feedback_time: entry
+                .get_var("feedback_time")
+                .parse()
+                .ok()
+                .filter(|time: &f32| time.is_finite())
+                .unwrap_or(default.feedback_time)
+                .clamp(MIN_FEEDBACK_TIME, MAX_FEEDBACK_TIME),
+            wait_for_enter: entry.get_var("wait_for_enter") == "true",
+            session_cap: entry
+                .get_var("session_cap")
+                .parse()
+                .unwrap_or(default.session_cap)
+                .min(MAX_SESSION_CAP),
+            requeue: match entry.get_var("requeue").as_str() {
+                "later" => RequeueMode::Later,
+                _ => RequeueMode::Back,
+            },
+            requeue_offset: entry
+                .get_var("requeue_offset")
+                .parse()
+                .unwrap_or(default.requeue_offset)
+                .clamp(MIN_REQUEUE_OFFSET, MAX_REQUEUE_OFFSET),
+        }
+    }
+
+    pub fn to_entry(&self) -> Entry {
+        let mut entry = Entry::new("learn");
+        entry.add_integer("mcq_reps", self.mcq_reps as i64);
+        entry.add_integer("tf_reps", self.tf_reps as i64);
+        entry.add_integer("frq_reps", self.frq_reps as i64);
+        entry.add_integer("mcq_options", self.mcq_options as i64);
+        entry.add_float("feedback_time", self.feedback_time);
+        entry.add_bool("wait_for_enter", self.wait_for_enter);
+        entry.add_integer("session_cap", self.session_cap as i64);
+        let requeue = match self.requeue {
+            RequeueMode::Back => "back",
+            RequeueMode::Later => "later",
+        };
+        entry.add_string("requeue", requeue);
+        entry.add_integer("requeue_offset", self.requeue_offset as i64);
+        entry
+    }
+}
 
 pub struct LearnState {
     flashcards: Vec<Flashcard>,
     pub mcq: VecDeque<Flashcard>,
+    pub tf: VecDeque<Flashcard>,
     pub frq: VecDeque<Flashcard>,
     pub size: usize,
     pub mcq_ans: Vec<String>,
+    //The answer shown with a true/false question, this is either the correct
+    //answer or a similar incorrect answer
+    pub tf_shown: String,
     timer: f32,
     pub answer: String,
     submitted: bool,
+    //How many times each card was answered in this session
+    attempts: HashMap<(String, String), u32>,
+    //How many times each card was answered incorrectly in this session
+    misses: HashMap<(String, String), u32>,
+    //Total time spent answering each card (in seconds)
+    response_times: HashMap<(String, String), f32>,
+    overrides: HashMap<(String, String), u32>,
+    skips: HashMap<(String, String), u32>,
+    hint_counts: HashMap<(String, String), u32>,
+    //Number of letters of the answer revealed for the current question
+    hints: usize,
+    //The player marked the submitted answer as correct
+    overridden: bool,
+    //The player did not know the answer to the current question
+    skipped: bool,
+    //Number of (correct, total) answers for each type of question
+    answer_counts: HashMap<QuestionType, (u32, u32)>,
+    //How long the current question has been displayed for
+    question_timer: f32,
+    //How long the session has lasted (in seconds)
+    pub elapsed: f32,
+    progress: Progress,
+    //File that the session is saved to when leaving before it is finished
+    save_path: String,
+    config: LearnConfig,
+    rng: StdRng,
+    //When the next card is due if nothing was due when the session started
+    pub next_due: Option<u64>,
+}
+
+#[derive(PartialEq, Eq)]
+enum CharClass {
+    //Digits but no letters (e.g. "42", "3/4", "-1.5")
+    Numeric,
+    //Both letters and digits (e.g. "NH4(+)")
+    Mixed,
+    //No digits
+    Text,
+}
+
+fn char_class(s: &str) -> CharClass {
+    let has_digit = s.chars().any(|ch| ch.is_ascii_digit());
+    let has_letter = s.chars().any(|ch| ch.is_alphabetic());
+    match (has_digit, has_letter) {
+        (true, false) => CharClass::Numeric,
+        (true, true) => CharClass::Mixed,
+        _ => CharClass::Text,
+    }
+}
+
+//Number of characters that two strings share at the start
+fn common_prefix_len<I: Iterator<Item = char>>(a: I, b: I) -> usize {
+    a.zip(b).take_while(|(ch1, ch2)| ch1 == ch2).count()
+}
+
+//How similar an incorrect answer is to the correct answer of `card`, more
+//similar answers make for better incorrect options in multiple choice
+fn similarity(card: &Flashcard, other: &Flashcard) -> f32 {
+    let (a, b) = (&card.answer, &other.answer);
+    let mut score = 0.0;
+
+    if !card.group.is_empty() && card.group == other.group {
+        score += 3.0;
+    }
+
+    if char_class(a) == char_class(b) {
+        score += 3.0;
+    }
+
+    //Similar length
+    let (len_a, len_b) = (a.chars().count() as f32, b.chars().count() as f32);
+    score += 2.0 * (1.0 - (len_a - len_b).abs() / len_a.max(len_b).max(1.0));
+
+    //Shared prefix and suffix
+    let prefix = common_prefix_len(a.chars(), b.chars()).min(3);
+    let suffix = common_prefix_len(a.chars().rev(), b.chars().rev()).min(3);
+    score += (prefix + suffix) as f32 * 0.5;
+
+    score
+}
+
+//Picks up to `count` distinct incorrect answers for `card` from `cards`,
+//answers similar to the correct answer are more likely to be picked
+fn pick_distractors(
+    card: &Flashcard,
+    cards: &[Flashcard],
+    count: usize,
+    rng: &mut StdRng,
+) -> Vec<String> {
+    let mut seen = HashSet::new();
+    seen.insert(card.answer.clone());
+    let mut candidates: Vec<(f32, &Flashcard)> = cards
+        .iter()
+        .filter(|other| seen.insert(other.answer.clone()))
+        //Add some randomness so that the same options do not always appear
+        .map(|other| (similarity(card, other) + rng.random::<f32>() * 2.0, other))
+        .collect();
+    candidates.sort_by(|(score1, _), (score2, _)| score2.total_cmp(score1));
+    candidates
+        .into_iter()
+        .take(count)
+        .map(|(_, other)| other.answer.clone())
+        .collect()
+}
+
+//Returns a queue with `reps` passes over `cards`, each pass is shuffled
+fn build_queue(cards: &[Flashcard], reps: u32, rng: &mut StdRng) -> VecDeque<Flashcard> {
+    let mut queue = VecDeque::new();
+    for _ in 0..reps {
+        let mut pass = cards.to_vec();
+        pass.shuffle(rng);
+        queue.extend(pass);
+    }
+    queue
+}
+
+//Creates an entry for a card in one of the queues of a saved session
+fn queue_entry(queue: &str, card: &Flashcard) -> Entry {
+    let mut entry = Entry::new(queue);
+    entry.add_string("question", &card.question);
+    entry.add_string("answer", &card.answer);
+    entry.add_string("group", &card.group);
+    entry
+}
+
+//Deletes a saved session
+pub fn delete_save(path: &str) {
+    if !Path::new(path).exists() {
+        return;
+    }
+
+    if let Err(msg) = std::fs::remove_file(path) {
+        eprintln!("Failed to delete: {path}");
+        eprintln!("{msg}");
+    }
 }
 
 impl LearnState {
-    pub fn new(cards: &[Flashcard]) -> Self {
-        let mut rng = rand::rng();
+    //Starts a session with the cards that are due for review and new cards,
+    //multiple choice answers are picked from all of `cards`, all random
+    //decisions in the session are made with an rng seeded with `seed`
+    pub fn new(
+        cards: &[Flashcard],
+        progress: Progress,
+        config: &LearnConfig,
+        save_path: &str,
+        seed: u64,
+    ) -> Self {
+        let mut session = progress.session_cards(cards, progress::now());
+        if config.session_cap > 0 {
+            session.truncate(config.session_cap);
+        }
+        let next_due = if session.is_empty() {
+            progress.next_due(cards)
+        } else {
+            None
+        };
+        let mut state = Self::with_session(&session, cards, progress, config, save_path, seed);
+        state.next_due = next_due;
+        state
+    }
 
-        let mut shuffled_mcq = cards.to_vec();
-        shuffled_mcq.shuffle(&mut rng);
+    //Starts a session with every card even if it is not due, this session
+    //does not affect the schedule of the cards and is not saved
+    pub fn study_all(cards: &[Flashcard], config: &LearnConfig, seed: u64) -> Self {
+        Self::with_session(cards, cards, Progress::empty(), config, "", seed)
+    }
 
-        let mut shuffled_frq = cards.to_vec();
-        shuffled_frq.shuffle(&mut rng);
-        shuffled_frq.extend(shuffled_frq.clone());
+    //Starts a session that only contains `missed`, this session does not
+    //affect the schedule of the cards and is not saved
+    pub fn review_missed(
+        missed: &[Flashcard],
+        cards: &[Flashcard],
+        config: &LearnConfig,
+        seed: u64,
+    ) -> Self {
+        Self::with_session(missed, cards, Progress::empty(), config, "", seed)
+    }
 
-        let sz = shuffled_mcq.len() + shuffled_frq.len();
+    fn with_session(
+        session: &[Flashcard],
+        cards: &[Flashcard],
+        progress: Progress,
+        config: &LearnConfig,
+        save_path: &str,
+        seed: u64,
+    ) -> Self {
+        //Each card should be asked at least once
+        let frq_reps = if config.mcq_reps == 0 && config.tf_reps == 0 {
+            config.frq_reps.max(1)
+        } else {
+            config.frq_reps
+        };
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mcq = build_queue(session, config.mcq_reps, &mut rng);
+        let tf = build_queue(session, config.tf_reps, &mut rng);
+        let frq = build_queue(session, frq_reps, &mut rng);
+        let sz = mcq.len() + tf.len() + frq.len();
 
         Self {
             flashcards: cards.to_vec(),
-            mcq: VecDeque::from(shuffled_mcq),
-            frq: VecDeque::from(shuffled_frq),
+            mcq,
+            tf,
+            frq,
             size: sz,
             mcq_ans: vec![],
+            tf_shown: String::new(),
             timer: 0.0,
             answer: String::new(),
             submitted: false,
+            attempts: HashMap::new(),
+            misses: HashMap::new(),
+            response_times: HashMap::new(),
+            overrides: HashMap::new(),
+            skips: HashMap::new(),
+            hint_counts: HashMap::new(),
+            hints: 0,
+            overridden: false,
+            skipped: false,
+            answer_counts: HashMap::new(),
+            question_timer: 0.0,
+            elapsed: 0.0,
+            progress,
+            save_path: save_path.to_string(),
+            config: config.clone(),
+            rng,
+            next_due: None,
+        }
+    }
+
+    //Loads a session that was saved before it was finished, returns None if
+    //there is no saved session at `save_path`
+    pub fn load(
+        cards: &[Flashcard],
+        progress: Progress,
+        config: &LearnConfig,
+        save_path: &str,
+        seed: u64,
+    ) -> Option<Self> {
+        if !Path::new(save_path).exists() {
+            return None;
+        }
+
+        let mut state = Self::empty();
+        state.flashcards = cards.to_vec();
+        state.progress = progress;
+        state.config = config.clone();
+        state.save_path = save_path.to_string();
+        state.rng = StdRng::seed_from_u64(seed);
+        for entry in impfile::parse_file(save_path) {
+            let card = Flashcard::new(
+                &entry.get_var("question"),
+                &entry.get_var("answer"),
+                &entry.get_var("group"),
+            );
+            match entry.get_name().as_str() {
+                "session" => {
+                    state.size = entry.get_var("size").parse().unwrap_or(0);
+                    state.elapsed = entry.get_var("time").parse().unwrap_or(0.0);
+                }
+                "answers" => {
+                    if let Some(question_type) = QuestionType::from_id(&entry.get_var("type")) {
+                        let correct = entry.get_var("correct").parse().unwrap_or(0);
+                        let total = entry.get_var("total").parse().unwrap_or(0);
+                        state.answer_counts.insert(question_type, (correct, total));
+                    }
+                }
+                "mcq" => state.mcq.push_back(card),
+                "tf" => state.tf.push_back(card),
+                "frq" => state.frq.push_back(card),
+                "stats" => {
+                    let attempts = entry.get_var("attempts").parse().unwrap_or(0);
+                    let misses = entry.get_var("misses").parse().unwrap_or(0);
+                    let response_time = entry.get_var("response_time").parse().unwrap_or(0.0);
+                    let overrides = entry.get_var("overrides").parse().unwrap_or(0);
+                    let skips = entry.get_var("skips").parse().unwrap_or(0);
+                    let hints = entry.get_var("hints").parse().unwrap_or(0);
+                    state.attempts.insert(card.key(), attempts);
+                    state.misses.insert(card.key(), misses);
+                    state.response_times.insert(card.key(), response_time);
+                    state.overrides.insert(card.key(), overrides);
+                    state.skips.insert(card.key(), skips);
+                    state.hint_counts.insert(card.key(), hints);
+                }
+                _ => {}
+            }
+        }
+
+        state.get_flashcard()?;
+        //Make sure the progress bar stays between 0 and 1
+        state.size = state.size.max(state.cards_left());
+        state.prepare_question();
+        Some(state)
+    }
+
+    //Saves the remaining cards so that the session can be resumed later,
+    //if the session is finished then the saved session is deleted instead
+    pub fn save(&mut self) {
+        if self.save_path.is_empty() {
+            return;
+        }
+
+        //Apply the answer that is currently being displayed
+        if self.submitted {
+            self.timer = 0.0;
+            self.update(0.0);
+        }
+
+        if self.get_flashcard().is_none() {
+            delete_save(&self.save_path);
+            return;
+        }
+
+        let mut session = Entry::new("session");
+        session.add_integer("size", self.size as i64);
+        session.add_float("time", self.elapsed);
+        let mut entries = vec![session];
+        for (question_type, (correct, total)) in &self.answer_counts {
+            let mut entry = Entry::new("answers");
+            entry.add_string("type", question_type.id());
+            entry.add_integer("correct", *correct as i64);
+            entry.add_integer("total", *total as i64);
+            entries.push(entry);
+        }
+        entries.extend(self.mcq.iter().map(|card| queue_entry("mcq", card)));
+        entries.extend(self.tf.iter().map(|card| queue_entry("tf", card)));
+        entries.extend(self.frq.iter().map(|card| queue_entry("frq", card)));
+        for ((question, answer), attempts) in &self.attempts {
+            let mut entry = Entry::new("stats");
+            entry.add_string("question", question);
+            entry.add_string("answer", answer);
+            entry.add_integer("attempts", *attempts as i64);
+            let key = (question.clone(), answer.clone());
+            let misses = self.misses.get(&key).copied().unwrap_or(0);
+            entry.add_integer("misses", misses as i64);
+            let response_time = self.response_times.get(&key).copied().unwrap_or(0.0);
+            entry.add_float("response_time", response_time);
+            let overrides = self.overrides.get(&key).copied().unwrap_or(0);
+            entry.add_integer("overrides", overrides as i64);
+            let skips = self.skips.get(&key).copied().unwrap_or(0);
+            entry.add_integer("skips", skips as i64);
+            let hints = self.hint_counts.get(&key).copied().unwrap_or(0);
+            entry.add_integer("hints", hints as i64);
+            entries.push(entry);
+        }
+
+        if let Err(msg) = impfile::write_file(&self.save_path, &entries) {
+            eprintln!("Failed to save session: {}", self.save_path);
+            eprintln!("{msg}");
         }
     }
 
@@ -45,57 +551,229 @@ impl LearnState {
         Self {
             flashcards: vec![],
             mcq: VecDeque::new(),
+            tf: VecDeque::new(),
             frq: VecDeque::new(),
             size: 0,
             mcq_ans: vec![],
+            tf_shown: String::new(),
             timer: 0.0,
             answer: String::new(),
             submitted: false,
+            attempts: HashMap::new(),
+            misses: HashMap::new(),
+            response_times: HashMap::new(),
+            overrides: HashMap::new(),
+            skips: HashMap::new(),
+            hint_counts: HashMap::new(),
+            hints: 0,
+            overridden: false,
+            skipped: false,
+            answer_counts: HashMap::new(),
+            question_timer: 0.0,
+            elapsed: 0.0,
+            progress: Progress::empty(),
+            save_path: String::new(),
+            config: LearnConfig::new(),
+            rng: StdRng::seed_from_u64(0),
+            next_due: None,
         }
     }
 
-    pub fn set_mcq_ans(&mut self) {
+    //Sets up the multiple choice options or the answer shown with a
+    //true/false question for the current card
+    pub fn prepare_question(&mut self) {
         self.mcq_ans.clear();
+        self.tf_shown.clear();
 
-        if self.mcq.is_empty() {
-            return;
-        }
-
-        let correct = rand::random::<u32>() % 4;
         let flashcard = self.get_flashcard().unwrap_or(Flashcard::none());
-        let mut skip = HashSet::new();
-        skip.insert(flashcard.answer.clone());
-        for i in 0..4 {
-            //Generate the correct answer
-            if i == correct {
-                self.mcq_ans.push(flashcard.answer.clone());
-                continue;
-            }
-
-            //Generate the incorrect answers
-            let mut index = rand::random::<u32>() as usize % self.flashcards.len();
-            let mut count = 0;
-            while skip.contains(&self.flashcards[index].answer) && count < self.flashcards.len() {
-                index += 1;
-                index %= self.flashcards.len();
-                count += 1;
+        match self.question_type() {
+            Some(QuestionType::MultipleChoice) => self.set_mcq_ans(&flashcard),
+            Some(QuestionType::TrueFalse) => {
+                //Show the correct answer half of the time
+                let distractor = pick_distractors(&flashcard, &self.flashcards, 1, &mut self.rng);
+                self.tf_shown = match distractor.first() {
+                    Some(wrong) if self.rng.random() => wrong.clone(),
+                    _ => flashcard.answer.clone(),
+                };
             }
-            self.mcq_ans.push(self.flashcards[index].answer.clone());
-            skip.insert(self.flashcards[index].answer.clone());
+            _ => {}
         }
     }
 
-    pub fn get_flashcard(&self) -> Option<Flashcard> {
+    fn set_mcq_ans(&mut self, flashcard: &Flashcard) {
+        let distractors = pick_distractors(
+            flashcard,
+            &self.flashcards,
+            self.config
+                .mcq_options
+                .clamp(MIN_MCQ_OPTIONS, MAX_MCQ_OPTIONS)
+                - 1,
+            &mut self.rng,
+        );
+        //Put the correct answer in a random position
+        let correct = self.rng.random_range(0..=distractors.len());
+        self.mcq_ans.extend(distractors);
+        self.mcq_ans.insert(correct, flashcard.answer.clone());
+    }
+
+    //Returns the type of the current question, None if the session is over
+    pub fn question_type(&self) -> Option<QuestionType> {
         if !self.mcq.is_empty() {
-            self.mcq.front().cloned()
+            Some(QuestionType::MultipleChoice)
+        } else if !self.tf.is_empty() {
+            Some(QuestionType::TrueFalse)
+        } else if !self.frq.is_empty() {
+            Some(QuestionType::FreeResponse)
         } else {
-            self.frq.front().cloned()
+            None
+        }
+    }
+
+    fn queue_mut(&mut self, question_type: QuestionType) -> &mut VecDeque<Flashcard> {
+        match question_type {
+            QuestionType::MultipleChoice => &mut self.mcq,
+            QuestionType::TrueFalse => &mut self.tf,
+            QuestionType::FreeResponse => &mut self.frq,
         }
     }
 
+    pub fn get_flashcard(&self) -> Option<Flashcard> {
+        self.mcq
+            .front()
+            .or(self.tf.front())
+            .or(self.frq.front())
+            .cloned()
+    }
+
+    fn cards_left(&self) -> usize {
+        self.mcq.len() + self.tf.len() + self.frq.len()
+    }
+
     pub fn percent(&self) -> f32 {
-        let left = self.mcq.len() + self.frq.len();
-        1.0 - left as f32 / self.size as f32
+        //Nothing to study
+        if self.size == 0 {
+            return 1.0;
+        }
+        1.0 - self.cards_left() as f32 / self.size as f32
+    }
+
+    //Returns true if the submitted answer is correct
+    pub fn answered_correctly(&self) -> bool {
+        if self.overridden {
+            return true;
+        } else if self.skipped {
+            return false;
+        }
+
+        let card = self.get_flashcard().unwrap_or(Flashcard::none());
+        match self.question_type() {
+            Some(QuestionType::TrueFalse) => {
+                let is_true = self.tf_shown == card.answer;
+                self.answer == is_true.to_string()
+            }
+            _ => check_answer(&card, &self.answer),
+        }
+    }
+
+    //Returns true if the player can mark their free response answer as correct
+    pub fn can_override(&self) -> bool {
+        self.display_correct()
+            && self.question_type() == Some(QuestionType::FreeResponse)
+            && !self.skipped
+            && !self.answered_correctly()
+    }
+
+    //Marks the submitted free response answer as correct, this is for answers
+    //that are acceptable but do not exactly match the card (e.g. synonyms)
+    pub fn override_correct(&mut self) {
+        if !self.can_override() {
+            return;
+        }
+
+        let card = self.get_flashcard().unwrap_or(Flashcard::none());
+        *self.overrides.entry(card.key()).or_insert(0) += 1;
+        self.overridden = true;
+    }
+
+    //The player does not know the answer to a free response question, the
+    //answer is revealed and the card counts as missed
+    pub fn skip(&mut self) {
+        if self.submitted || self.question_type() != Some(QuestionType::FreeResponse) {
+            return;
+        }
+
+        let card = self.get_flashcard().unwrap_or(Flashcard::none());
+        *self.skips.entry(card.key()).or_insert(0) += 1;
+        self.skipped = true;
+        self.submit("");
+    }
+
+    pub fn skipped(&self) -> bool {
+        self.skipped
+    }
+
+    //Returns true if the player can reveal another letter of the answer
+    pub fn can_hint(&self) -> bool {
+        let card = self.get_flashcard().unwrap_or(Flashcard::none());
+        let letters = card.answer.chars().filter(|ch| !ch.is_whitespace()).count();
+        !self.submitted
+            && self.question_type() == Some(QuestionType::FreeResponse)
+            && self.hints < letters
+    }
+
+    //Reveals the next letter of the answer to a free response question
+    pub fn hint(&mut self) {
+        if !self.can_hint() {
+            return;
+        }
+
+        let card = self.get_flashcard().unwrap_or(Flashcard::none());
+        *self.hint_counts.entry(card.key()).or_insert(0) += 1;
+        self.hints += 1;
+    }
+
+    pub fn hints_used(&self) -> bool {
+        self.hints > 0
+    }
+
+    //Returns the answer with the unrevealed letters replaced with underscores
+    //(e.g. `b _ _ _ _`), words are separated by a wider gap
+    pub fn hint_text(&self) -> String {
+        let card = self.get_flashcard().unwrap_or(Flashcard::none());
+        let mut revealed = 0;
+        card.answer
+            .split_whitespace()
+            .map(|word| {
+                word.chars()
+                    .map(|ch| {
+                        revealed += 1;
+                        if revealed <= self.hints {
+                            ch.to_string()
+                        } else {
+                            "_".to_string()
+                        }
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect::<Vec<String>>()
+            .join("     ")
+    }
+
+    //Returns the number of words and letters in the answer
+    pub fn answer_length(&self) -> (usize, usize) {
+        let card = self.get_flashcard().unwrap_or(Flashcard::none());
+        let words = card.answer.split_whitespace().count();
+        let letters = card.answer.chars().filter(|ch| !ch.is_whitespace()).count();
+        (words, letters)
+    }
+
+    //Answers a true/false question
+    pub fn submit_true_false(&mut self, value: bool) {
+        if self.question_type() != Some(QuestionType::TrueFalse) {
+            return;
+        }
+        self.submit(&value.to_string());
     }
 
     pub fn submit(&mut self, ans: &str) {
@@ -103,47 +781,158 @@ impl LearnState {
             return;
         }
 
+        let card = self.get_flashcard().unwrap_or(Flashcard::none());
+        *self.response_times.entry(card.key()).or_insert(0.0) += self.question_timer;
+        self.question_timer = 0.0;
         self.answer = ans.to_string();
         self.submitted = true;
-        self.timer = DISPLAY_CORRECT_ANS_TIMER;
+        self.timer = if self.config.wait_for_enter {
+            f32::INFINITY
+        } else {
+            self.config.feedback_time
+        };
+    }
+
+    //Returns true if the correct answer is being displayed until the player
+    //presses enter to continue
+    pub fn waiting_to_continue(&self) -> bool {
+        self.submitted && self.timer == f32::INFINITY
+    }
+
+    //Stop displaying the correct answer and move on to the next question
+    pub fn continue_to_next(&mut self) {
+        if self.submitted {
+            self.timer = 0.0;
+        }
+    }
+
+    //Moves the card at the front of a queue to later in that queue
+    fn requeue_front(&mut self, question_type: QuestionType) {
+        let requeue = self.config.requeue;
+        self.requeue_front_as(question_type, requeue);
+    }
+
+    fn requeue_front_as(&mut self, question_type: QuestionType, requeue: RequeueMode) {
+        let offset = self.config.requeue_offset.max(1);
+        let queue = self.queue_mut(question_type);
+        if let Some(card) = queue.pop_front() {
+            match requeue {
+                RequeueMode::Back => queue.push_back(card),
+                RequeueMode::Later => queue.insert(offset.min(queue.len()), card),
+            }
+        }
     }
 
     pub fn display_correct(&self) -> bool {
         self.timer > 0.0 && self.submitted
     }
 
+    //Called after a card is answered correctly, once the card no longer
+    //appears in any queue its review is sent to the scheduler
+    fn card_completed(&mut self, card: &Flashcard) {
+        if self.mcq.contains(card) || self.tf.contains(card) || self.frq.contains(card) {
+            return;
+        }
+
+        let misses = self.misses.get(&card.key()).copied().unwrap_or(0);
+        let mut quality = progress::review_quality(misses);
+        //Answers that needed hints only count as partially remembered
+        if self.hint_counts.get(&card.key()).copied().unwrap_or(0) > 0 {
+            quality = quality.min(HINT_REVIEW_QUALITY);
+        }
+        self.progress.review(card, quality);
+        self.progress.save();
+    }
+
+    fn card_missed(&mut self, card: &Flashcard) {
+        *self.misses.entry(card.key()).or_insert(0) += 1;
+    }
+
+    //Returns the percentage of questions of a type that were answered
+    //correctly, None if no questions of that type were answered
+    pub fn accuracy(&self, question_type: QuestionType) -> Option<f32> {
+        match self.answer_counts.get(&question_type) {
+            Some((correct, total)) if *total > 0 => Some(*correct as f32 / *total as f32),
+            _ => None,
+        }
+    }
+
+    //Returns the average time taken to answer a question (in seconds)
+    pub fn average_response_time(&self) -> f32 {
+        let total_attempts: u32 = self.attempts.values().sum();
+        if total_attempts == 0 {
+            return 0.0;
+        }
+        self.response_times.values().sum::<f32>() / total_attempts as f32
+    }
+
+    //Returns the statistics of the cards that were answered incorrectly,
+    //sorted from the most missed card to the least missed card
+    pub fn most_missed(&self) -> Vec<CardStats> {
+        let mut missed: Vec<CardStats> = self
+            .flashcards
+            .iter()
+            .filter_map(|card| {
+                let misses = self.misses.get(&card.key()).copied().unwrap_or(0);
+                if misses == 0 {
+                    return None;
+                }
+                Some(CardStats {
+                    card: card.clone(),
+                    attempts: self.attempts.get(&card.key()).copied().unwrap_or(0),
+                    misses,
+                    overrides: self.overrides.get(&card.key()).copied().unwrap_or(0),
+                    skips: self.skips.get(&card.key()).copied().unwrap_or(0),
+                    hints: self.hint_counts.get(&card.key()).copied().unwrap_or(0),
+                })
+            })
+            .collect();
+        missed.sort_by_key(|stats| std::cmp::Reverse(stats.misses));
+        missed
+    }
+
     pub fn update(&mut self, dt: f32) {
+        if self.get_flashcard().is_some() {
+            self.elapsed += dt;
+        }
+
         if self.submitted {
             self.timer -= dt;
+        } else {
+            self.question_timer += dt;
         }
 
         if self.timer <= 0.0 && self.submitted {
             let card = self.get_flashcard().unwrap_or(Flashcard::none());
-            if !self.mcq.is_empty() {
-                //Handle multiple choice
-                if self.answer == card.answer {
-                    self.mcq.pop_front();
-                    self.set_mcq_ans();
-                } else {
-                    let card = self.mcq.pop_front();
-                    if let Some(card) = card {
-                        self.mcq.push_back(card);
-                    }
-                    self.set_mcq_ans();
+            *self.attempts.entry(card.key()).or_insert(0) += 1;
+            if let Some(question_type) = self.question_type() {
+                let correct = self.answered_correctly();
+                //Answers that needed hints are only partially correct
+                let partial = correct && self.hints_used();
+                let (correct_count, total) =
+                    self.answer_counts.entry(question_type).or_insert((0, 0));
+                *total += 1;
+                if correct && !partial {
+                    *correct_count += 1;
                 }
-            } else {
-                //Handle free response
-                if self.answer == card.answer {
-                    self.frq.pop_front();
+
+                if partial {
+                    //Ask the card again a few cards later
+                    self.requeue_front_as(question_type, RequeueMode::Later);
+                } else if correct {
+                    self.queue_mut(question_type).pop_front();
+                    self.card_completed(&card);
                 } else {
-                    let card = self.frq.pop_front();
-                    if let Some(card) = card {
-                        self.frq.push_back(card);
-                    }
+                    self.card_missed(&card);
+                    self.requeue_front(question_type);
                 }
             }
+            self.prepare_question();
             self.answer.clear();
             self.submitted = false;
+            self.overridden = false;
+            self.skipped = false;
+            self.hints = 0;
         }
     }
 }
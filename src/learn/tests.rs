@@ -0,0 +1,78 @@
+#[cfg(test)]
+use super::*;
+
+#[test]
+fn test_config_clamped() {
+    let mut entry = Entry::new("learn");
+    entry.add_string("mcq_reps", "100");
+    entry.add_string("tf_reps", "4");
+    entry.add_string("frq_reps", "1000000");
+    entry.add_string("mcq_options", "1");
+    entry.add_string("feedback_time", "NaN");
+    entry.add_string("session_cap", "99999");
+    entry.add_string("requeue_offset", "0");
+    let config = LearnConfig::from_entry(&entry);
+    assert_eq!(config.mcq_reps, MAX_REPS);
+    assert_eq!(config.tf_reps, MAX_REPS);
+    assert_eq!(config.frq_reps, MAX_REPS);
+    assert_eq!(config.mcq_options, MIN_MCQ_OPTIONS);
+    assert_eq!(config.feedback_time, LearnConfig::new().feedback_time);
+    assert_eq!(config.session_cap, MAX_SESSION_CAP);
+    assert_eq!(config.requeue_offset, MIN_REQUEUE_OFFSET);
+
+    entry.add_string("feedback_time", "inf");
+    let config = LearnConfig::from_entry(&entry);
+    assert_eq!(config.feedback_time, LearnConfig::new().feedback_time);
+    entry.add_string("feedback_time", "100");
+    let config = LearnConfig::from_entry(&entry);
+    assert_eq!(config.feedback_time, MAX_FEEDBACK_TIME);
+}
+
+#[test]
+fn test_char_class() {
+    assert!(char_class("42") == CharClass::Numeric);
+    assert!(char_class("-1.5") == CharClass::Numeric);
+    assert!(char_class("NH4(+)") == CharClass::Mixed);
+    assert!(char_class("Paris") == CharClass::Text);
+}
+
+#[test]
+fn test_similarity() {
+    let card = Flashcard::new("6 * 7", "42", "math");
+    let close = Flashcard::new("5 * 9", "45", "math");
+    let far = Flashcard::new("Capital of France", "Paris", "geography");
+    assert!(similarity(&card, &close) > similarity(&card, &far));
+}
+
+#[test]
+fn test_distractors_distinct() {
+    let card = Flashcard::new("1 + 1", "2", "math");
+    let cards = vec![
+        card.clone(),
+        Flashcard::new("2 * 1", "2", "math"),
+        Flashcard::new("2 + 1", "3", "math"),
+        Flashcard::new("1 + 2", "3", "math"),
+        Flashcard::new("2 + 2", "4", "math"),
+        Flashcard::new("2 + 3", "5", "math"),
+    ];
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..20 {
+        let distractors = pick_distractors(&card, &cards, 3, &mut rng);
+        assert_eq!(distractors.len(), 3);
+        assert!(!distractors.contains(&card.answer));
+        let unique: HashSet<&String> = distractors.iter().collect();
+        assert_eq!(unique.len(), distractors.len());
+    }
+}
+
+#[test]
+fn test_distractors_small_set() {
+    let card = Flashcard::new("1 + 1", "2", "math");
+    let cards = vec![card.clone(), Flashcard::new("2 + 1", "3", "math")];
+    let mut rng = StdRng::seed_from_u64(0);
+    let distractors = pick_distractors(&card, &cards, MAX_MCQ_OPTIONS - 1, &mut rng);
+    assert_eq!(distractors, ["3"]);
+
+    let distractors = pick_distractors(&card, &cards[..1], MAX_MCQ_OPTIONS - 1, &mut rng);
+    assert!(distractors.is_empty());
+}
@@ -1,17 +1,29 @@
 #![windows_subsystem = "windows"]
 
 mod assets;
+mod compose;
+mod exam;
 mod flashcards;
 mod game;
 mod gfx;
 mod gui;
+mod highscores;
 mod impfile;
 mod learn;
 mod log;
+mod matching;
+mod progress;
+mod replay;
+mod settings;
+mod timestep;
+mod userdata;
 
 use game::{Game, GameScreen};
 use glfw::{Context, WindowMode};
 use gui::GuiController;
+use highscores::ScoreMode;
+use replay::Replay;
+use timestep::{FixedTimestep, TIMESTEP};
 
 //Load `assets/icon.png` as the window icon
 fn load_icon(window: &mut glfw::Window) {
@@ -27,9 +39,8 @@ fn load_icon(window: &mut glfw::Window) {
     }
 }
 
-fn run_game(gamestate: &mut Game, gui_controller: &mut GuiController, dt: f32) {
+fn run_game(gamestate: &mut Game, gui_controller: &mut GuiController) {
     gamestate.draw();
-    gamestate.update(dt);
     //Display gui
     gui::set_ui_gl_state();
     let gui_action = gui_controller.display_game_gui(gamestate);
@@ -38,10 +49,9 @@ fn run_game(gamestate: &mut Game, gui_controller: &mut GuiController, dt: f32) {
     }
 }
 
-fn run_main_menu(gamestate: &mut Game, gui_controller: &mut GuiController, dt: f32) {
+fn run_main_menu(gamestate: &mut Game, gui_controller: &mut GuiController) {
     //Display background
     gamestate.draw_background_only();
-    gamestate.update_time(dt);
     //Display gui
     gui::set_ui_gl_state();
     let gui_action = gui_controller.display_main_menu_gui(gamestate);
@@ -50,10 +60,9 @@ fn run_main_menu(gamestate: &mut Game, gui_controller: &mut GuiController, dt: f
     }
 }
 
-fn run_about_screen(gamestate: &mut Game, gui_controller: &mut GuiController, dt: f32) {
+fn run_about_screen(gamestate: &mut Game, gui_controller: &mut GuiController) {
     //Display background
     gamestate.draw_background_only();
-    gamestate.update_time(dt);
     //Display gui
     gui::set_ui_gl_state();
     let gui_action = gui_controller.display_about_screen(gamestate);
@@ -62,10 +71,9 @@ fn run_about_screen(gamestate: &mut Game, gui_controller: &mut GuiController, dt
     }
 }
 
-fn run_load_flashcards(gamestate: &mut Game, gui_controller: &mut GuiController, dt: f32) {
+fn run_load_flashcards(gamestate: &mut Game, gui_controller: &mut GuiController) {
     //Display background
     gamestate.draw_background_only();
-    gamestate.update_time(dt);
     //Display gui
     gui::set_ui_gl_state();
     let gui_action = gui_controller.display_load_screen(gamestate);
@@ -74,11 +82,42 @@ fn run_load_flashcards(gamestate: &mut Game, gui_controller: &mut GuiController,
     }
 }
 
-fn run_learn(gamestate: &mut Game, gui_controller: &mut GuiController, dt: f32) {
+fn run_match(gamestate: &mut Game, gui_controller: &mut GuiController) {
+    //Display background
+    gamestate.draw_background_only();
+    //Display gui
+    gui::set_ui_gl_state();
+    let gui_action = gui_controller.display_match_gui(gamestate);
+    if let Some(action) = gui_action {
+        gui::handle_gui_action(gamestate, action);
+    }
+}
+
+fn run_exam(gamestate: &mut Game, gui_controller: &mut GuiController) {
+    //Display background
+    gamestate.draw_background_only();
+    //Display gui
+    gui::set_ui_gl_state();
+    let gui_action = gui_controller.display_exam_gui(gamestate);
+    if let Some(action) = gui_action {
+        gui::handle_gui_action(gamestate, action);
+    }
+}
+
+fn run_settings(gamestate: &mut Game, gui_controller: &mut GuiController) {
+    //Display background
+    gamestate.draw_background_only();
+    //Display gui
+    gui::set_ui_gl_state();
+    let gui_action = gui_controller.display_settings_screen(gamestate);
+    if let Some(action) = gui_action {
+        gui::handle_gui_action(gamestate, action);
+    }
+}
+
+fn run_learn(gamestate: &mut Game, gui_controller: &mut GuiController) {
     //Display background
     gamestate.draw_background_only();
-    gamestate.update_time(dt);
-    gamestate.learn_state.update(dt);
     //Display gui
     gui::set_ui_gl_state();
     let gui_action = gui_controller.display_learn_gui(gamestate);
@@ -87,8 +126,70 @@ fn run_learn(gamestate: &mut Game, gui_controller: &mut GuiController, dt: f32)
     }
 }
 
+//Advances the current screen by one simulation step
+fn step(gamestate: &mut Game, dt: f32) {
+    match gamestate.current_screen {
+        GameScreen::Game => gamestate.update(dt),
+        GameScreen::Learn => {
+            gamestate.update_time(dt);
+            if !gamestate.learn_paused {
+                gamestate.learn_state.update(dt);
+            }
+        }
+        GameScreen::Match => {
+            gamestate.update_time(dt);
+            gamestate.match_state.update(dt);
+            if gamestate.match_state.finished() {
+                gamestate.record_high_score(ScoreMode::Match);
+            }
+        }
+        GameScreen::Exam => {
+            gamestate.update_time(dt);
+            gamestate.exam_state.update(dt);
+        }
+        GameScreen::MainMenu
+        | GameScreen::About
+        | GameScreen::LoadFlashcards
+        | GameScreen::Settings => gamestate.update_time(dt),
+    }
+}
+
+struct Args {
+    card_paths: Vec<String>,
+    seed: Option<u64>,
+    //Path to a replay to watch
+    replay: Option<String>,
+}
+
+//Splits the command line arguments into the flashcard paths and the values
+//of the `--seed` and `--replay` options (if they are given)
+fn parse_args(args: &[String]) -> Args {
+    let mut parsed = Args {
+        card_paths: vec![],
+        seed: None,
+        replay: None,
+    };
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--seed" => match iter.next().map(|value| value.parse::<u64>()) {
+                Some(Ok(value)) => parsed.seed = Some(value),
+                Some(Err(msg)) => eprintln!("Invalid seed: {msg}"),
+                None => eprintln!("Missing value for --seed"),
+            },
+            "--replay" => match iter.next() {
+                Some(path) => parsed.replay = Some(path.clone()),
+                None => eprintln!("Missing value for --replay"),
+            },
+            _ => parsed.card_paths.push(arg.clone()),
+        }
+    }
+    parsed
+}
+
 fn main() {
     let args: Vec<String> = std::env::args().collect();
+    let args = parse_args(&args[1..]);
     let mut glfw = glfw::init(glfw::fail_on_errors).expect("Failed to init glfw!");
     //Init window
     let (mut window, events) = glfw
@@ -108,26 +209,46 @@ fn main() {
 
     //Initialize game
     let mut gamestate = Game::new();
+    if let Some(seed) = args.seed {
+        gamestate.set_seed(seed);
+    }
     //Load config
     gamestate.load_config("cfg.impfile");
+    //Load difficulty presets
+    gamestate.load_difficulties("assets/difficulty.impfile");
+    //Load user settings
+    gamestate.load_settings();
     //Load about text
     gamestate.load_about();
     //Load assets
     gamestate.load_assets();
     gamestate.init_window_dimensions(window.get_size());
     //Load flashcards
-    gamestate.flashcards = flashcards::load_flashcards(&args[1..]);
+    gamestate.set_flashcards(flashcards::load_flashcards(&args.card_paths));
     if gamestate.flashcards.is_empty() {
         gamestate.current_screen = GameScreen::MainMenu;
+    } else {
+        gamestate.start_round();
+    }
+    //Watch a replay
+    if let Some(replay) = args.replay.as_deref().and_then(Replay::load) {
+        gamestate.start_playback(replay);
     }
     //gui controller
     let mut gui_controller = GuiController::init(&window);
     gui_controller.init_font(&gamestate);
 
     let mut dt = 0.0f32;
+    let mut timestep = FixedTimestep::new();
     while !window.should_close() {
         let start = std::time::Instant::now();
 
+        //Simulate in fixed steps so that the game does not depend on the
+        //frame rate
+        for _ in 0..timestep.advance(dt) {
+            step(&mut gamestate, TIMESTEP);
+        }
+
         //Clear screen
         gfx::set_default_gl_state();
         gfx::clear();
@@ -138,13 +259,14 @@ fn main() {
         gui_controller.update_state(w, h, gamestate.time(), pixels_per_point);
 
         match gamestate.current_screen {
-            GameScreen::Game => run_game(&mut gamestate, &mut gui_controller, dt),
-            GameScreen::MainMenu => run_main_menu(&mut gamestate, &mut gui_controller, dt),
-            GameScreen::About => run_about_screen(&mut gamestate, &mut gui_controller, dt),
-            GameScreen::LoadFlashcards => {
-                run_load_flashcards(&mut gamestate, &mut gui_controller, dt)
-            }
-            GameScreen::Learn => run_learn(&mut gamestate, &mut gui_controller, dt),
+            GameScreen::Game => run_game(&mut gamestate, &mut gui_controller),
+            GameScreen::MainMenu => run_main_menu(&mut gamestate, &mut gui_controller),
+            GameScreen::About => run_about_screen(&mut gamestate, &mut gui_controller),
+            GameScreen::LoadFlashcards => run_load_flashcards(&mut gamestate, &mut gui_controller),
+            GameScreen::Learn => run_learn(&mut gamestate, &mut gui_controller),
+            GameScreen::Match => run_match(&mut gamestate, &mut gui_controller),
+            GameScreen::Exam => run_exam(&mut gamestate, &mut gui_controller),
+            GameScreen::Settings => run_settings(&mut gamestate, &mut gui_controller),
         }
 
         //Print OpenGL errors
@@ -156,4 +278,13 @@ fn main() {
         gamestate.process_events(&events, &mut gui_controller);
         dt = start.elapsed().as_secs_f32();
     }
+
+    //Save any unfinished learn session
+    if gamestate.current_screen == GameScreen::Learn {
+        gamestate.learn_state.save();
+    }
+    //Save the game that was being played
+    if gamestate.current_screen == GameScreen::Game {
+        gamestate.save_replay();
+    }
 }
@@ -0,0 +1,183 @@
+/*
+ * Matching mode: the player is shown a few questions and their answers in a
+ * random order and has to match each question with its answer
+ * */
+
+use crate::{
+    flashcards::Flashcard,
+    progress::{self, Progress},
+};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use std::collections::HashMap;
+
+pub const PAIRS_PER_ROUND: usize = 6;
+//How long a wrong match is highlighted for (in seconds)
+const WRONG_MATCH_TIME: f32 = 0.6;
+const MATCH_POINTS: u64 = 100;
+const WRONG_MATCH_PENALTY: u64 = 25;
+//Bonus points for each second under the par time of a round
+const TIME_BONUS: u64 = 5;
+//Number of seconds per pair that a round should take
+const PAR_TIME_PER_PAIR: f32 = 5.0;
+
+pub struct MatchState {
+    //Cards that have not appeared in a round yet
+    remaining: Vec<Flashcard>,
+    //Cards in the current round, these are the questions in order
+    pub round: Vec<Flashcard>,
+    //Order of the answers, these are indices into `round`
+    pub answer_order: Vec<usize>,
+    //Which questions and answers have been matched (indices into `round`)
+    pub matched_questions: Vec<bool>,
+    pub matched_answers: Vec<bool>,
+    pub selected: Option<usize>,
+    //(question, answer) that was just matched incorrectly
+    pub wrong_match: Option<(usize, usize)>,
+    wrong_match_timer: f32,
+    round_time: f32,
+    pub time: f32,
+    pub score: u64,
+    pub mistakes: u32,
+    pub size: usize,
+    //How many times each card was matched incorrectly
+    misses: HashMap<(String, String), u32>,
+    progress: Progress,
+    rng: StdRng,
+}
+
+impl MatchState {
+    pub fn new(cards: &[Flashcard], progress: Progress, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut remaining = cards.to_vec();
+        remaining.shuffle(&mut rng);
+        let mut state = Self {
+            remaining,
+            round: vec![],
+            answer_order: vec![],
+            matched_questions: vec![],
+            matched_answers: vec![],
+            selected: None,
+            wrong_match: None,
+            wrong_match_timer: 0.0,
+            round_time: 0.0,
+            time: 0.0,
+            score: 0,
+            mistakes: 0,
+            size: cards.len(),
+            misses: HashMap::new(),
+            progress,
+            rng,
+        };
+        state.next_round();
+        state
+    }
+
+    pub fn empty() -> Self {
+        Self::new(&[], Progress::empty(), 0)
+    }
+
+    fn next_round(&mut self) {
+        let count = PAIRS_PER_ROUND.min(self.remaining.len());
+        self.round = self.remaining.split_off(self.remaining.len() - count);
+        self.answer_order = (0..self.round.len()).collect();
+        self.answer_order.shuffle(&mut self.rng);
+        self.matched_questions = vec![false; self.round.len()];
+        self.matched_answers = vec![false; self.round.len()];
+        self.selected = None;
+        self.round_time = 0.0;
+    }
+
+    pub fn finished(&self) -> bool {
+        self.round.is_empty()
+    }
+
+    //Returns the fraction of match attempts that were correct
+    pub fn accuracy(&self) -> f32 {
+        let attempts = self.size as u32 + self.mistakes;
+        if attempts == 0 {
+            return 0.0;
+        }
+        self.size as f32 / attempts as f32
+    }
+
+    pub fn percent(&self) -> f32 {
+        if self.size == 0 {
+            return 1.0;
+        }
+        let unmatched = self
+            .matched_questions
+            .iter()
+            .filter(|matched| !**matched)
+            .count();
+        1.0 - (self.remaining.len() + unmatched) as f32 / self.size as f32
+    }
+
+    //Selects a question to match with an answer
+    pub fn select(&mut self, question: usize) {
+        if question < self.round.len() && !self.matched_questions[question] {
+            self.selected = Some(question);
+        }
+    }
+
+    //Attempts to match the selected question with the answer of `answer`
+    //(an index into `round`)
+    pub fn match_answer(&mut self, answer: usize) {
+        let Some(question) = self.selected else {
+            return;
+        };
+
+        if answer >= self.round.len() || self.matched_answers[answer] {
+            return;
+        }
+
+        //Cards can share the same answer so compare the answer text
+        if self.round[question].answer == self.round[answer].answer {
+            self.matched_questions[question] = true;
+            self.matched_answers[answer] = true;
+            self.score += MATCH_POINTS;
+        } else {
+            let card = self.round[question].clone();
+            *self.misses.entry(card.key()).or_insert(0) += 1;
+            self.mistakes += 1;
+            self.score = self.score.saturating_sub(WRONG_MATCH_PENALTY);
+            self.wrong_match = Some((question, answer));
+            self.wrong_match_timer = WRONG_MATCH_TIME;
+        }
+        self.selected = None;
+
+        if self.matched_questions.iter().all(|matched| *matched) {
+            self.finish_round();
+        }
+    }
+
+    //Awards a time bonus and sends the results of the round to the scheduler
+    fn finish_round(&mut self) {
+        let par_time = PAR_TIME_PER_PAIR * self.round.len() as f32;
+        let bonus = (par_time - self.round_time).max(0.0) as u64 * TIME_BONUS;
+        self.score += bonus;
+
+        for card in &self.round {
+            let misses = self.misses.get(&card.key()).copied().unwrap_or(0);
+            self.progress.review(card, progress::review_quality(misses));
+        }
+        self.progress.save();
+        self.next_round();
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        if self.finished() {
+            return;
+        }
+
+        self.time += dt;
+        self.round_time += dt;
+        if self.wrong_match_timer > 0.0 {
+            self.wrong_match_timer -= dt;
+            if self.wrong_match_timer <= 0.0 {
+                self.wrong_match = None;
+            }
+        }
+    }
+}
@@ -0,0 +1,264 @@
+/*
+ * Spaced repetition progress for a set (using the SM-2 algorithm), this is
+ * saved per player and per set in the user data directory
+ * */
+
+mod tests;
+
+use crate::{
+    flashcards::{Flashcard, SET_PATH},
+    impfile::{self, Entry},
+    userdata,
+};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: u64 = 60 * 60 * 24;
+pub const DEFAULT_EASE: f32 = 2.5;
+const MIN_EASE: f32 = 1.3;
+//Maximum number of cards that have never been studied in a session
+const NEW_CARDS_PER_SESSION: usize = 20;
+
+//Returns the current time in seconds since the unix epoch
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+//Converts the number of times a card was missed in a session into how well
+//it was remembered (0 - 5) for the scheduler
+pub fn review_quality(misses: u32) -> u32 {
+    match misses {
+        0 => 5,
+        1 => 3,
+        _ => 1,
+    }
+}
+
+//Replaces any characters that should not be in a file name
+fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .map(|ch| if ch.is_alphanumeric() { ch } else { '_' })
+        .collect()
+}
+
+//FNV-1a, used instead of the std hasher because file names have to stay the
+//same between versions of Rust
+fn hash_name(name: &str) -> u32 {
+    name.bytes().fold(0x811c9dc5, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x01000193)
+    })
+}
+
+//Sanitizes a name for use in a file name, if any characters had to be
+//replaced then a hash of the original name is added so that different names
+//do not end up with the same file name
+fn unique_name(name: &str) -> String {
+    let sanitized = sanitize_name(name);
+    if sanitized == name {
+        return sanitized;
+    }
+    format!("{sanitized}-{:08x}", hash_name(name))
+}
+
+//Returns the name of a set from its path
+pub fn set_name(set_path: &str) -> String {
+    let name = std::path::Path::new(set_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or(set_path.to_string());
+    sanitize_name(&name)
+}
+
+//Returns a name that is different for every set, this is the name of the
+//set followed by a hash of its path (relative to `SET_PATH` if it is in it)
+pub fn set_key(set_path: &str) -> String {
+    let path = std::path::Path::new(set_path);
+    let relative = path.strip_prefix(SET_PATH).unwrap_or(path);
+    let relative = relative.to_string_lossy().replace('\\', "/");
+    format!("{}-{:08x}", set_name(set_path), hash_name(&relative))
+}
+
+//Returns the path to a file in `dir` (in the user data directory) that stores
+//data for `player` on the set at `set_path`
+pub fn player_set_path(dir: &str, player: &str, set_path: &str) -> String {
+    let file_name = format!("{}.impfile", set_key(set_path));
+    player_file_path(dir, player, &file_name)
+}
+
+//Returns the path to `file_name` in the directory for `player` in `dir`
+pub fn player_file_path(dir: &str, player: &str, file_name: &str) -> String {
+    let player = if player.is_empty() {
+        "player".to_string()
+    } else {
+        unique_name(player)
+    };
+    userdata::data_path(&format!("{dir}/{player}/{file_name}"))
+}
+
+#[derive(Clone)]
+pub struct CardProgress {
+    pub ease: f32,
+    //In days
+    pub interval: u64,
+    //When the card should be reviewed next (unix time in seconds)
+    pub due: u64,
+    //Number of times in a row the card has been answered correctly
+    pub reps: u32,
+    //Number of times the card was forgotten after being learned
+    pub lapses: u32,
+}
+
+impl CardProgress {
+    pub fn new() -> Self {
+        Self {
+            ease: DEFAULT_EASE,
+            interval: 0,
+            due: 0,
+            reps: 0,
+            lapses: 0,
+        }
+    }
+
+    //Updates the schedule of a card, `quality` is how well the card was
+    //remembered from 0 (not at all) to 5 (perfectly)
+    pub fn review(&mut self, quality: u32, time: u64) {
+        let quality = quality.min(5);
+        if quality >= 3 {
+            self.interval = match self.reps {
+                0 => 1,
+                1 => 6,
+                _ => (self.interval as f32 * self.ease).round() as u64,
+            };
+            self.reps += 1;
+        } else {
+            if self.reps > 0 {
+                self.lapses += 1;
+            }
+            self.reps = 0;
+            self.interval = 1;
+        }
+
+        let q = (5 - quality) as f32;
+        self.ease = (self.ease + 0.1 - q * (0.08 + q * 0.02)).max(MIN_EASE);
+        self.due = time + self.interval * SECONDS_PER_DAY;
+    }
+
+    fn from_entry(entry: &Entry) -> Self {
+        Self {
+            ease: entry.get_var("ease").parse().unwrap_or(DEFAULT_EASE),
+            interval: entry.get_var("interval").parse().unwrap_or(0),
+            due: entry.get_var("due").parse().unwrap_or(0),
+            reps: entry.get_var("reps").parse().unwrap_or(0),
+            lapses: entry.get_var("lapses").parse().unwrap_or(0),
+        }
+    }
+}
+
+pub struct Progress {
+    path: String,
+    //(question, answer) -> progress
+    cards: HashMap<(String, String), CardProgress>,
+}
+
+impl Progress {
+    //Progress that is not saved anywhere
+    pub fn empty() -> Self {
+        Self {
+            path: String::new(),
+            cards: HashMap::new(),
+        }
+    }
+
+    //Loads the progress of `player` on the set at `set_path`
+    pub fn load(player: &str, set_path: &str) -> Self {
+        let path = player_set_path("progress", player, set_path);
+        let mut progress = Self {
+            path,
+            cards: HashMap::new(),
+        };
+
+        if !std::path::Path::new(&progress.path).exists() {
+            return progress;
+        }
+
+        for entry in impfile::parse_file(&progress.path) {
+            let question = entry.get_var("question");
+            let answer = entry.get_var("answer");
+            let card_progress = CardProgress::from_entry(&entry);
+            progress.cards.insert((question, answer), card_progress);
+        }
+
+        progress
+    }
+
+    pub fn save(&self) {
+        if self.path.is_empty() {
+            return;
+        }
+
+        let entries: Vec<Entry> = self
+            .cards
+            .iter()
+            .map(|((question, answer), card)| {
+                let mut entry = Entry::new("card");
+                entry.add_string("question", question);
+                entry.add_string("answer", answer);
+                entry.add_float("ease", card.ease);
+                entry.add_integer("interval", card.interval as i64);
+                entry.add_integer("due", card.due as i64);
+                entry.add_integer("reps", card.reps as i64);
+                entry.add_integer("lapses", card.lapses as i64);
+                entry
+            })
+            .collect();
+
+        if let Err(msg) = impfile::write_file(&self.path, &entries) {
+            eprintln!("Failed to save progress: {}", self.path);
+            eprintln!("{msg}");
+        }
+    }
+
+    pub fn get(&self, card: &Flashcard) -> Option<&CardProgress> {
+        self.cards.get(&card.key())
+    }
+
+    pub fn review(&mut self, card: &Flashcard, quality: u32) {
+        self.cards
+            .entry(card.key())
+            .or_insert(CardProgress::new())
+            .review(quality, now());
+    }
+
+    //Returns the cards that should be studied in a session: every card that
+    //is due for review (most overdue first) followed by some new cards
+    pub fn session_cards(&self, cards: &[Flashcard], time: u64) -> Vec<Flashcard> {
+        let mut due: Vec<(u64, Flashcard)> = cards
+            .iter()
+            .filter_map(|card| self.get(card).map(|p| (p.due, card.clone())))
+            .filter(|(due, _)| *due <= time)
+            .collect();
+        due.sort_by_key(|(due, _)| *due);
+
+        let new_cards = cards
+            .iter()
+            .filter(|card| self.get(card).is_none())
+            .take(NEW_CARDS_PER_SESSION)
+            .cloned();
+
+        due.into_iter()
+            .map(|(_, card)| card)
+            .chain(new_cards)
+            .collect()
+    }
+
+    //Returns when the first of `cards` that has been studied is due next
+    pub fn next_due(&self, cards: &[Flashcard]) -> Option<u64> {
+        cards
+            .iter()
+            .filter_map(|card| self.get(card).map(|p| p.due))
+            .min()
+    }
+}
@@ -0,0 +1,97 @@
+#[cfg(test)]
+use super::*;
+
+#[test]
+fn test_interval_growth() {
+    let mut card = CardProgress::new();
+    card.review(5, 0);
+    assert_eq!(card.interval, 1);
+    assert_eq!(card.due, SECONDS_PER_DAY);
+    card.review(5, 0);
+    assert_eq!(card.interval, 6);
+    let ease = card.ease;
+    card.review(5, 0);
+    assert_eq!(card.interval, (6.0 * ease).round() as u64);
+    assert_eq!(card.reps, 3);
+    assert_eq!(card.due, card.interval * SECONDS_PER_DAY);
+}
+
+#[test]
+fn test_ease_floor() {
+    let mut card = CardProgress::new();
+    for _ in 0..20 {
+        card.review(0, 0);
+    }
+    assert_eq!(card.ease, MIN_EASE);
+
+    //Perfect answers raise the ease
+    card.review(5, 0);
+    assert!(card.ease > MIN_EASE);
+}
+
+#[test]
+fn test_lapse_resets_interval() {
+    let mut card = CardProgress::new();
+    for _ in 0..4 {
+        card.review(5, 0);
+    }
+    assert!(card.interval > 6);
+    card.review(1, 100);
+    assert_eq!(card.interval, 1);
+    assert_eq!(card.reps, 0);
+    assert_eq!(card.lapses, 1);
+    assert_eq!(card.due, 100 + SECONDS_PER_DAY);
+
+    //Missing a card that was never learned is not a lapse
+    let mut card = CardProgress::new();
+    card.review(1, 0);
+    assert_eq!(card.lapses, 0);
+}
+
+#[test]
+fn test_session_cards() {
+    let cards = vec![
+        Flashcard::new("a", "1", "group"),
+        Flashcard::new("b", "2", "group"),
+        Flashcard::new("c", "3", "group"),
+        Flashcard::new("d", "4", "group"),
+    ];
+    let mut progress = Progress::empty();
+    let mut reviewed = |card: &Flashcard, due: u64| {
+        let mut card_progress = CardProgress::new();
+        card_progress.due = due;
+        progress.cards.insert(card.key(), card_progress);
+    };
+    //Due, the most overdue card comes first
+    reviewed(&cards[0], 50);
+    reviewed(&cards[1], 10);
+    //Not due yet
+    reviewed(&cards[2], 1000);
+
+    let session = progress.session_cards(&cards, 100);
+    let questions: Vec<&str> = session.iter().map(|card| card.question.as_str()).collect();
+    assert_eq!(questions, ["b", "a", "d"]);
+
+    //Nothing due and no new cards, nothing to study
+    assert!(progress.session_cards(&cards[2..3], 100).is_empty());
+    assert_eq!(progress.next_due(&cards[2..3]), Some(1000));
+    assert_eq!(progress.next_due(&cards), Some(10));
+    assert_eq!(progress.next_due(&cards[3..]), None);
+}
+
+#[test]
+fn test_set_keys_unique() {
+    let key = set_key("sets/a/vocab.impfile");
+    assert!(key.starts_with("vocab_impfile-"));
+    assert_ne!(key, set_key("sets/b/vocab.impfile"));
+    assert_eq!(key, set_key("sets/a/vocab.impfile"));
+    //Relative to the sets directory
+    assert_eq!(key, set_key("a/vocab.impfile"));
+}
+
+#[test]
+fn test_player_names_unique() {
+    assert_eq!(unique_name("player"), "player");
+    assert_ne!(unique_name("Ann B"), unique_name("Ann_B"));
+    assert!(unique_name("Ann B").starts_with("Ann_B-"));
+}
@@ -0,0 +1,223 @@
+/*
+ * Replays of the asteroid game: a game is recorded as its seed, its cards
+ * and the answers the player submitted (and power-ups they used) along with
+ * the simulation step they were submitted on. Feeding the inputs back in on
+ * the same steps reproduces the game exactly
+ * */
+
+mod tests;
+
+use crate::{
+    flashcards::Flashcard,
+    game::{difficulty::Difficulty, powerup::PowerUp, selection::CardSelection},
+    impfile::{self, Entry},
+    progress,
+};
+use std::path::Path;
+
+#[derive(Clone)]
+pub enum ReplayInput {
+    Answer(String),
+    PowerUp(PowerUp),
+}
+
+pub struct Replay {
+    pub seed: u64,
+    //Name of the set that was played
+    pub set_name: String,
+    pub cards: Vec<Flashcard>,
+    //How cards were picked and the weight of each card from saved progress
+    pub selection: CardSelection,
+    pub priors: Vec<f32>,
+    pub difficulty: Difficulty,
+    //(simulation step, input)
+    inputs: Vec<(u64, ReplayInput)>,
+    //Index of the next input to play back
+    next: usize,
+}
+
+//Characters that would break an impfile value are percent-encoded so that
+//any typed answer can be saved and played back exactly
+fn encode_answer(answer: &str) -> String {
+    let mut encoded = String::new();
+    for ch in answer.chars() {
+        if matches!(ch, '%' | '"' | '#' | '{' | '}' | ';' | '=') || ch.is_ascii_control() {
+            encoded.push_str(&format!("%{:02X}", ch as u32));
+        } else {
+            encoded.push(ch);
+        }
+    }
+    encoded
+}
+
+fn decode_answer(encoded: &str) -> String {
+    let mut decoded = String::new();
+    let mut chars = encoded.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '%' {
+            decoded.push(ch);
+            continue;
+        }
+        let hex: String = chars.by_ref().take(2).collect();
+        match u8::from_str_radix(&hex, 16) {
+            Ok(byte) => decoded.push(byte as char),
+            //Not something we encoded, keep it as it is
+            Err(_) => {
+                decoded.push('%');
+                decoded.push_str(&hex);
+            }
+        }
+    }
+    decoded
+}
+
+impl Replay {
+    pub fn new(seed: u64, set_name: &str, cards: &[Flashcard]) -> Self {
+        Self {
+            seed,
+            set_name: set_name.to_string(),
+            cards: cards.to_vec(),
+            selection: CardSelection::Uniform,
+            priors: vec![],
+            difficulty: Difficulty::new(),
+            inputs: vec![],
+            next: 0,
+        }
+    }
+
+    //Loads a replay, returns None if the file could not be read
+    pub fn load(path: &str) -> Option<Self> {
+        if !Path::new(path).exists() {
+            eprintln!("Replay not found: {path}");
+            return None;
+        }
+
+        let mut replay = Self::new(0, "", &[]);
+        let mut found_header = false;
+        for entry in impfile::parse_file(path) {
+            match entry.get_name().as_str() {
+                "replay" => {
+                    replay.seed = entry.get_var("seed").parse().unwrap_or(0);
+                    replay.set_name = entry.get_var("set");
+                    replay.selection = CardSelection::from_id(&entry.get_var("selection"));
+                    found_header = true;
+                }
+                "card" => {
+                    let card = Flashcard::new(
+                        &entry.get_var("question"),
+                        &entry.get_var("answer"),
+                        &entry.get_var("group"),
+                    );
+                    replay.cards.push(card);
+                    replay
+                        .priors
+                        .push(entry.get_var("weight").parse().unwrap_or(0.0));
+                }
+                "difficulty" => replay.difficulty = Difficulty::from_entry(&entry),
+                "answer" => {
+                    let Ok(step) = entry.get_var("step").parse() else {
+                        continue;
+                    };
+                    let answer = ReplayInput::Answer(decode_answer(&entry.get_var("answer")));
+                    replay.inputs.push((step, answer));
+                }
+                "powerup" => {
+                    let Ok(step) = entry.get_var("step").parse() else {
+                        continue;
+                    };
+                    let Some(powerup) = PowerUp::from_id(&entry.get_var("powerup")) else {
+                        continue;
+                    };
+                    replay.inputs.push((step, ReplayInput::PowerUp(powerup)));
+                }
+                _ => {}
+            }
+        }
+
+        if !found_header {
+            eprintln!("Invalid replay: {path}");
+            return None;
+        }
+        replay.inputs.sort_by_key(|(step, _)| *step);
+        Some(replay)
+    }
+
+    //Saves the replay in the replays directory for the player
+    pub fn save(&self, player: &str) {
+        let file_name = format!("{}-{}.impfile", self.set_name, progress::now());
+        let path = progress::player_file_path("replays", player, &file_name);
+        self.save_to(&path);
+    }
+
+    pub fn save_to(&self, path: &str) {
+        let mut entries = vec![];
+        let mut header = Entry::new("replay");
+        header.add_string("seed", &self.seed.to_string());
+        header.add_string("set", &self.set_name);
+        header.add_string("selection", self.selection.id());
+        entries.push(header);
+        entries.push(self.difficulty.to_entry("difficulty"));
+        for (i, card) in self.cards.iter().enumerate() {
+            let mut entry = Entry::new("card");
+            entry.add_string("question", &card.question);
+            entry.add_string("answer", &card.answer);
+            entry.add_string("group", &card.group);
+            entry.add_float("weight", self.priors.get(i).copied().unwrap_or(0.0));
+            entries.push(entry);
+        }
+        for (step, input) in &self.inputs {
+            let mut entry = match input {
+                ReplayInput::Answer(answer) => {
+                    let mut entry = Entry::new("answer");
+                    entry.add_string("answer", &encode_answer(answer));
+                    entry
+                }
+                ReplayInput::PowerUp(powerup) => {
+                    let mut entry = Entry::new("powerup");
+                    entry.add_string("powerup", powerup.id());
+                    entry
+                }
+            };
+            entry.add_string("step", &step.to_string());
+            entries.push(entry);
+        }
+
+        if let Err(msg) = impfile::write_file(path, &entries) {
+            eprintln!("Failed to save replay: {path}");
+            eprintln!("{msg}");
+        }
+    }
+
+    //Records an answer that was submitted after `step` simulation steps
+    pub fn record(&mut self, step: u64, answer: &str) {
+        let answer = ReplayInput::Answer(answer.to_string());
+        self.inputs.push((step, answer));
+    }
+
+    //Records a power-up that was used after `step` simulation steps
+    pub fn record_powerup(&mut self, step: u64, powerup: PowerUp) {
+        self.inputs.push((step, ReplayInput::PowerUp(powerup)));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    //Returns the inputs that were submitted after `step` simulation steps
+    pub fn inputs_at(&mut self, step: u64) -> Vec<ReplayInput> {
+        let mut inputs = vec![];
+        while let Some((input_step, input)) = self.inputs.get(self.next) {
+            if *input_step > step {
+                break;
+            }
+            inputs.push(input.clone());
+            self.next += 1;
+        }
+        inputs
+    }
+
+    //Start playing back from the beginning
+    pub fn rewind(&mut self) {
+        self.next = 0;
+    }
+}
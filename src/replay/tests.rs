@@ -0,0 +1,40 @@
+#[cfg(test)]
+use super::*;
+
+#[test]
+fn test_replay_round_trip() {
+    let cards = [Flashcard::new("question", "answer", "group")];
+    let mut replay = Replay::new(42, "set", &cards);
+    let answers = [
+        "answer",
+        "say \"hi\"",
+        "# not a comment",
+        "{braces}",
+        "a;b=c",
+        "100%",
+        "%41",
+        "naïve",
+    ];
+    for (i, answer) in answers.iter().enumerate() {
+        replay.record(i as u64, answer);
+    }
+
+    let path = std::env::temp_dir().join("test_replay_round_trip.impfile");
+    let path = path.to_string_lossy().to_string();
+    replay.save_to(&path);
+    let loaded = Replay::load(&path);
+    let _ = std::fs::remove_file(&path);
+
+    let mut loaded = loaded.expect("replay should load");
+    assert_eq!(loaded.seed, 42);
+    assert_eq!(loaded.set_name, "set");
+    assert_eq!(loaded.cards.len(), 1);
+    let inputs = loaded.inputs_at(answers.len() as u64);
+    assert_eq!(inputs.len(), answers.len());
+    for (input, answer) in inputs.iter().zip(answers) {
+        match input {
+            ReplayInput::Answer(loaded_answer) => assert_eq!(loaded_answer, answer),
+            ReplayInput::PowerUp(_) => panic!("expected an answer"),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+/*
+ * Settings that the user can change in the settings menu, these are saved
+ * in the user data directory
+ * */
+
+use crate::{
+    exam::ExamConfig,
+    game::{
+        difficulty::{self, Difficulty},
+        selection::CardSelection,
+    },
+    impfile::{self, Entry},
+    learn::LearnConfig,
+    userdata,
+};
+
+const SETTINGS_PATH: &str = "settings.impfile";
+
+//Removes the characters that would end a value or an entry early when the
+//name is saved in an impfile
+pub fn clean_player_name(name: &str) -> String {
+    name.chars()
+        .filter(|ch| !matches!(ch, '"' | '#' | '{' | '}') && !ch.is_control())
+        .collect()
+}
+
+pub struct Settings {
+    //Progress is saved separately for each player
+    pub player_name: String,
+    //Replace sequences such as `e'` with `é` while typing an answer
+    pub compose_enabled: bool,
+    //Name of the compose table to use (see `assets/compose.impfile`)
+    pub compose_language: String,
+    //How cards are picked for asteroids
+    pub card_selection: CardSelection,
+    //Name of the difficulty preset or "custom"
+    pub difficulty: String,
+    pub custom_difficulty: Difficulty,
+    pub learn: LearnConfig,
+    pub exam: ExamConfig,
+}
+
+impl Settings {
+    pub fn new() -> Self {
+        Self {
+            player_name: "player".to_string(),
+            compose_enabled: false,
+            compose_language: String::new(),
+            card_selection: CardSelection::Uniform,
+            difficulty: difficulty::DEFAULT_DIFFICULTY.to_string(),
+            custom_difficulty: Difficulty::new(),
+            learn: LearnConfig::new(),
+            exam: ExamConfig::new(),
+        }
+    }
+
+    fn from_entry(entry: &Entry) -> Self {
+        let player_name = entry.get_var("player_name");
+        let difficulty = entry.get_var("difficulty");
+        Self {
+            player_name: if player_name.is_empty() {
+                "player".to_string()
+            } else {
+                player_name
+            },
+            compose_enabled: entry.get_var("compose_enabled") == "true",
+            compose_language: entry.get_var("compose_language"),
+            card_selection: CardSelection::from_id(&entry.get_var("card_selection")),
+            difficulty: if difficulty.is_empty() {
+                difficulty::DEFAULT_DIFFICULTY.to_string()
+            } else {
+                difficulty
+            },
+            custom_difficulty: Difficulty::new(),
+            learn: LearnConfig::new(),
+            exam: ExamConfig::new(),
+        }
+    }
+
+    fn to_entry(&self) -> Entry {
+        let mut entry = Entry::new("settings");
+        entry.add_string("player_name", &clean_player_name(&self.player_name));
+        entry.add_bool("compose_enabled", self.compose_enabled);
+        entry.add_string("compose_language", &self.compose_language);
+        entry.add_string("card_selection", self.card_selection.id());
+        entry.add_string("difficulty", &self.difficulty);
+        entry
+    }
+
+    //Loads the settings, returns the default settings if no settings were saved
+    pub fn load() -> Self {
+        let path = userdata::data_path(SETTINGS_PATH);
+        if !std::path::Path::new(&path).exists() {
+            return Self::new();
+        }
+
+        let entries = impfile::parse_file(&path);
+        let mut settings = entries
+            .iter()
+            .find(|e| e.get_name() == "settings")
+            .map(Self::from_entry)
+            .unwrap_or(Self::new());
+        if let Some(learn) = entries.iter().find(|e| e.get_name() == "learn") {
+            settings.learn = LearnConfig::from_entry(learn);
+        }
+        if let Some(exam) = entries.iter().find(|e| e.get_name() == "exam") {
+            settings.exam = ExamConfig::from_entry(exam);
+        }
+        if let Some(custom) = entries.iter().find(|e| e.get_name() == "custom_difficulty") {
+            settings.custom_difficulty = Difficulty::from_entry(custom);
+        }
+        settings
+    }
+
+    pub fn save(&self) {
+        let path = userdata::data_path(SETTINGS_PATH);
+        let entries = [
+            self.to_entry(),
+            self.learn.to_entry(),
+            self.exam.to_entry(),
+            self.custom_difficulty.to_entry("custom_difficulty"),
+        ];
+        if let Err(msg) = impfile::write_file(&path, &entries) {
+            eprintln!("Failed to save settings: {path}");
+            eprintln!("{msg}");
+        }
+    }
+}
@@ -0,0 +1,36 @@
+/*
+ * Fixed timestep: the game is simulated in steps of the same length no
+ * matter how long each frame takes to render, so the same inputs always
+ * produce the same game state
+ * */
+
+mod tests;
+
+pub const TIMESTEP: f32 = 1.0 / 120.0;
+//If a frame takes longer than this many steps (for example while the window
+//is being dragged), the extra time is dropped instead of being caught up
+const MAX_STEPS_PER_FRAME: u32 = 12;
+
+pub struct FixedTimestep {
+    //Time that has passed but has not been simulated yet
+    accumulator: f32,
+}
+
+impl FixedTimestep {
+    pub fn new() -> Self {
+        Self { accumulator: 0.0 }
+    }
+
+    //Adds the length of the last frame and returns how many steps should be
+    //simulated this frame
+    pub fn advance(&mut self, dt: f32) -> u32 {
+        self.accumulator += dt.max(0.0);
+        let steps = (self.accumulator / TIMESTEP) as u32;
+        if steps > MAX_STEPS_PER_FRAME {
+            self.accumulator = 0.0;
+            return MAX_STEPS_PER_FRAME;
+        }
+        self.accumulator -= steps as f32 * TIMESTEP;
+        steps
+    }
+}
@@ -0,0 +1,37 @@
+#[cfg(test)]
+use super::*;
+
+#[test]
+fn test_frame_split_into_steps() {
+    let mut timestep = FixedTimestep::new();
+    assert_eq!(timestep.advance(TIMESTEP * 3.5), 3);
+    assert_eq!(timestep.advance(TIMESTEP * 0.25), 0);
+}
+
+#[test]
+fn test_leftover_carried() {
+    let mut timestep = FixedTimestep::new();
+    assert_eq!(timestep.advance(TIMESTEP * 2.5), 2);
+    //Half a step was left over from the last frame
+    assert_eq!(timestep.advance(TIMESTEP * 0.75), 1);
+    assert_eq!(timestep.advance(TIMESTEP * 0.5), 0);
+    assert_eq!(timestep.advance(TIMESTEP * 0.5), 1);
+}
+
+#[test]
+fn test_max_steps_per_frame() {
+    let mut timestep = FixedTimestep::new();
+    assert_eq!(timestep.advance(1.0), MAX_STEPS_PER_FRAME);
+    //The time that was not simulated is dropped
+    assert_eq!(timestep.advance(TIMESTEP * 0.5), 0);
+    assert_eq!(timestep.advance(TIMESTEP * 0.75), 1);
+}
+
+#[test]
+fn test_negative_dt() {
+    let mut timestep = FixedTimestep::new();
+    assert_eq!(timestep.advance(TIMESTEP * 0.5), 0);
+    assert_eq!(timestep.advance(-1.0), 0);
+    //The negative frame did not take away the accumulated time
+    assert_eq!(timestep.advance(TIMESTEP * 0.75), 1);
+}
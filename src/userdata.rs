@@ -0,0 +1,33 @@
+/*
+ * Paths for data that is saved for the user (settings, progress, etc.)
+ * */
+
+use std::path::Path;
+
+//Returns the directory where user data is stored
+pub fn data_dir() -> String {
+    #[cfg(unix)]
+    if let Ok(home_dir) = std::env::var("HOME") {
+        return format!("{home_dir}/.astrocards/userdata");
+    }
+
+    #[cfg(windows)]
+    if let Ok(appdata) = std::env::var("APPDATA") {
+        return format!("{appdata}/astrocards");
+    }
+
+    "userdata".to_string()
+}
+
+//Returns the path to `name` in the user data directory, the directory is
+//created if it does not exist yet
+pub fn data_path(name: &str) -> String {
+    let path = format!("{}/{name}", data_dir());
+    if let Some(parent) = Path::new(&path).parent()
+        && let Err(msg) = std::fs::create_dir_all(parent)
+    {
+        eprintln!("Failed to create directory: {}", parent.display());
+        eprintln!("{msg}");
+    }
+    path
+}